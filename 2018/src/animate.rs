@@ -0,0 +1,144 @@
+use crate::Error;
+use std::io::Write;
+use std::time::Duration;
+
+// Terminal animation of grid simulations
+
+/// ANSI foreground colors available for rendering cells
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Color {
+    Default,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+impl Color {
+    fn ansi_code(&self) -> &'static str {
+        match self {
+            Color::Default => "\x1b[0m",
+            Color::Red => "\x1b[31m",
+            Color::Green => "\x1b[32m",
+            Color::Yellow => "\x1b[33m",
+            Color::Blue => "\x1b[34m",
+            Color::Magenta => "\x1b[35m",
+            Color::Cyan => "\x1b[36m",
+            Color::White => "\x1b[37m",
+        }
+    }
+}
+
+/// A simulation that can be drawn one frame at a time
+pub trait Frame {
+    /// Width and height of the current frame
+    fn size(&self) -> (usize, usize);
+
+    /// Glyph and color to draw at a given `(x, y)`
+    fn cell(&self, x: usize, y: usize) -> (char, Color);
+
+    /// Advances the simulation by one frame. Returns false once there is nothing left to animate
+    fn advance(&mut self) -> bool;
+}
+
+/// Controls how frames are drawn to the terminal
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// Pause between frames
+    pub delay: Duration,
+    /// Frames are clipped to this width and height
+    pub max_size: (usize, usize),
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            delay: Duration::from_millis(100),
+            max_size: (200, 60),
+        }
+    }
+}
+
+/// Renders the current frame as ANSI-colored text, clipped to `max_size`
+pub fn render<F: Frame>(frame: &F, max_size: (usize, usize)) -> String {
+    let (width, height) = frame.size();
+    let mut out = String::new();
+    for y in 0..height.min(max_size.1) {
+        let mut color = Color::Default;
+        for x in 0..width.min(max_size.0) {
+            let (glyph, next_color) = frame.cell(x, y);
+            if next_color != color {
+                out.push_str(next_color.ansi_code());
+                color = next_color;
+            }
+            out.push(glyph);
+        }
+        if color != Color::Default {
+            out.push_str(Color::Default.ansi_code());
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Draws every frame of the simulation to `out`, clearing the screen between frames
+///
+/// Returns the number of frames drawn
+pub fn run<F: Frame, W: Write>(frame: &mut F, opts: &Options, out: &mut W) -> Result<usize, Error> {
+    let mut count = 0;
+    loop {
+        write!(out, "\x1b[H\x1b[2J{}", render(frame, opts.max_size))?;
+        out.flush()?;
+        count += 1;
+        if !frame.advance() {
+            break;
+        }
+        std::thread::sleep(opts.delay);
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Blinker(bool);
+
+    impl Frame for Blinker {
+        fn size(&self) -> (usize, usize) {
+            (2, 1)
+        }
+
+        fn cell(&self, x: usize, _y: usize) -> (char, Color) {
+            match (x, self.0) {
+                (0, true) => ('#', Color::Red),
+                _ => ('.', Color::Default),
+            }
+        }
+
+        fn advance(&mut self) -> bool {
+            self.0 = !self.0;
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        assert_eq!(render(&Blinker(false), (10, 10)), "..\n");
+        assert_eq!(render(&Blinker(true), (10, 10)), "\x1b[31m#\x1b[0m.\n");
+        assert_eq!(render(&Blinker(true), (1, 10)), "\x1b[31m#\x1b[0m\n");
+    }
+
+    #[test]
+    fn test_run() {
+        let opts = Options {
+            delay: Duration::from_millis(0),
+            ..Options::default()
+        };
+        let mut out = Vec::new();
+        assert_eq!(run(&mut Blinker(false), &opts, &mut out).unwrap(), 2);
+    }
+}
@@ -1,6 +1,7 @@
 use std::error::Error;
 use std::fs::File;
 use std::io::Read;
+use std::time::Duration;
 
 macro_rules! bail {
     ($e:expr) => {{
@@ -17,6 +18,32 @@ fn main() {
     let mut args = ::std::env::args();
     let _ = args.next();
 
+    // Separate flags from the positional puzzle and file arguments
+    let mut animate = false;
+    let mut animate_opts = aoc::animate::Options::default();
+    let mut svg_path = None;
+    let mut positional = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--animate" => animate = true,
+            "--svg" => {
+                let path = args
+                    .next()
+                    .unwrap_or_else(|| bail!("--svg requires an output file"));
+                svg_path = Some(path);
+            }
+            "--delay" => {
+                let ms = args
+                    .next()
+                    .and_then(|ms| ms.parse().ok())
+                    .unwrap_or_else(|| bail!("--delay requires a number of milliseconds"));
+                animate_opts.delay = Duration::from_millis(ms);
+            }
+            _ => positional.push(arg),
+        }
+    }
+    let mut args = positional.into_iter();
+
     let puzzle = args
         .next()
         .expect("Must specifiy a puzzle (e.g. 2-1 for day 2 part 1)");
@@ -41,11 +68,32 @@ fn main() {
     file.read_to_string(&mut input)
         .expect("Failed reading file");
 
-    let output = aoc::apply(aoc::Input {
+    let input = aoc::Input {
         day,
         part,
         input: input.trim().to_owned(),
-    });
+    };
+
+    if let Some(path) = svg_path {
+        match aoc::svg(input) {
+            Ok(svg) => {
+                if let Err(err) = svg.save(&path) {
+                    bail!("Unable to write SVG file: {}", err);
+                }
+            }
+            Err(err) => print_cause_chain(&*err),
+        }
+        return;
+    }
+
+    if animate {
+        if let Err(err) = aoc::animate(input, &animate_opts) {
+            print_cause_chain(&*err);
+        }
+        return;
+    }
+
+    let output = aoc::apply(input);
     match output {
         Ok(res) => println!("{}", res),
         Err(err) => print_cause_chain(&*err),
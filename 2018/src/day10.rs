@@ -1,6 +1,9 @@
+use fxhash::FxHashSet;
 use regex::Regex;
-use std::cmp::{max, min};
-use std::i32::MAX as MAX_I32;
+use std::cmp::{max, min, Reverse};
+use std::convert::TryFrom;
+use crate::animate::{self, Color};
+use crate::ocr;
 use crate::Error;
 
 // 2018 AoC Day 10 puzzle
@@ -69,54 +72,208 @@ fn step_points(points: &mut [Point]) {
     }
 }
 
-// We're going to assume the points start by moving inward, namely "shrinking the grid"
-// and that the smallest grid is the one containing the message, after which point, the grid will expand indefinitely
-// Also, I don't really have it in me to do ASCII-Art-to-String, so we're going to return the entire ASCII Art
+// Positions of the points after `t` seconds, failing if any of them move out of range
+fn points_at(points: &[Point], t: i64) -> Result<Vec<Point>, Error> {
+    let at = |pos: i32, vel: i32| {
+        (vel as i64)
+            .checked_mul(t)
+            .and_then(|d| d.checked_add(pos as i64))
+            .and_then(|pos| i32::try_from(pos).ok())
+    };
+    points
+        .iter()
+        .map(|p| match (at(p.x, p.dx), at(p.y, p.dy)) {
+            (Some(x), Some(y)) => Ok(Point { x, y, ..*p }),
+            _ => Err(format!("Points move out of range after {} seconds", t).into()),
+        })
+        .collect()
+}
+
+// Area of the bounding box after `t` seconds. This saturates instead of overflowing, since a box
+// that large is never the smallest one.
+fn area_at(points: &[Point], t: i64) -> i64 {
+    let at = |pos: i32, vel: i32| (vel as i64).saturating_mul(t).saturating_add(pos as i64);
+    let (mut pmin, mut pmax) = ((i64::MAX, i64::MAX), (i64::MIN, i64::MIN));
+    for p in points {
+        let (x, y) = (at(p.x, p.dx), at(p.y, p.dy));
+        pmin = (min(pmin.0, x), min(pmin.1, y));
+        pmax = (max(pmax.0, x), max(pmax.1, y));
+    }
+    let span = |lo: i64, hi: i64| hi.saturating_sub(lo).saturating_add(1);
+    span(pmin.0, pmax.0).saturating_mul(span(pmin.1, pmax.1))
+}
+
+// Integer times on either side of each point where the top line of the upper envelope of
+// `pos + vel * t` changes. Lines are given as (vel, pos).
+fn envelope_break_times(mut lines: Vec<(i64, i64)>) -> Vec<i64> {
+    // Of any parallel lines only the highest can be on top
+    lines.sort_unstable_by_key(|&(vel, pos)| (vel, Reverse(pos)));
+    lines.dedup_by_key(|line| line.0);
+
+    // With lines in order of slope, the middle of three is never on top if the last one overtakes
+    // the first no later than the middle one does
+    let mut hull: Vec<(i64, i64)> = Vec::new();
+    for line in lines {
+        while let [.., (v1, p1), (v2, p2)] = hull[..] {
+            // Cross-multiplied comparison of the times (p1 - p3) / (v3 - v1) and (p1 - p2) / (v2 - v1)
+            let (v3, p3) = line;
+            let last_crossing = (p1 - p3) as i128 * (v2 - v1) as i128;
+            let middle_crossing = (p1 - p2) as i128 * (v3 - v1) as i128;
+            if last_crossing > middle_crossing {
+                break;
+            }
+            hull.pop();
+        }
+        hull.push(line);
+    }
+
+    let mut times = Vec::new();
+    for pair in hull.windows(2) {
+        let ((v1, p1), (v2, p2)) = (pair[0], pair[1]);
+        let (num, den) = (p1 - p2, v2 - v1);
+        times.push(num.div_euclid(den));
+        times.push(-(-num).div_euclid(den));
+    }
+    times
+}
+
+// The message appears when the points are packed most tightly, so this finds the earliest time the
+// bounding box has its smallest area. Each edge of the box is an envelope of lines like
+// max(p + v * t), so the width and height are convex and piecewise linear. Between breakpoints
+// both are linear, and the area is either monotonic (slopes with the same sign) or concave
+// (opposite signs). Either way its smallest value over the integers in that stretch is at one
+// end, leaving only the integer times on each side of a breakpoint to check.
+fn convergence_time(points: &[Point]) -> i64 {
+    let xs: Vec<_> = points.iter().map(|p| (p.dx as i64, p.x as i64)).collect();
+    let ys: Vec<_> = points.iter().map(|p| (p.dy as i64, p.y as i64)).collect();
+
+    let mut candidates = vec![0];
+    for upper in [xs, ys] {
+        // The lower envelope is the upper envelope of the lines flipped upside down
+        let lower = upper.iter().map(|&(vel, pos)| (-vel, -pos)).collect();
+        candidates.extend(envelope_break_times(upper));
+        candidates.extend(envelope_break_times(lower));
+    }
+    candidates.retain(|&t| t >= 0);
+    candidates.sort_unstable();
+    candidates.dedup();
+
+    candidates
+        .into_iter()
+        .min_by_key(|&t| area_at(points, t))
+        .unwrap()
+}
+
+// Returns the message as text when it's written in the known puzzle font, or as ASCII art otherwise
 pub fn part1(input: &str) -> Result<String, Error> {
-    let mut points = parse_input(input)?;
+    let points = parse_input(input)?;
+    if points.is_empty() {
+        return Err("No points in the sky".into());
+    }
+
+    let points = points_at(&points, convergence_time(&points))?;
+    let (pmin, pmax) = bounding_box(&points);
+    let grid = render_grid(&points, pmin, pmax);
+    Ok(ocr::decode(&grid).unwrap_or(grid))
+}
+
+// The number of seconds until the message appears
+pub fn part2(input: &str) -> Result<i64, Error> {
+    let points = parse_input(input)?;
+    if points.is_empty() {
+        return Err("No points in the sky".into());
+    }
+    Ok(convergence_time(&points))
+}
+
+// Animates the points converging into the message, starting once they fit on screen
+pub fn animate(input: &str, opts: &animate::Options) -> Result<(), Error> {
+    let mut sky = Sky::new(parse_input(input)?, opts.max_size);
+    sky.skip_until_fits()?;
+    animate::run(&mut sky, opts, &mut ::std::io::stdout())?;
+    Ok(())
+}
+
+// The moving points along with a lookup of which coordinates are lit in the current frame
+struct Sky {
+    points: Vec<Point>,
+    lit: FxHashSet<(i32, i32)>,
+    pmin: Coord,
+    pmax: Coord,
+    max_size: (usize, usize),
+}
 
-    let mut last_height = MAX_I32;
-    let mut grid = String::new();
-    loop {
+impl Sky {
+    fn new(points: Vec<Point>, max_size: (usize, usize)) -> Sky {
         let (pmin, pmax) = bounding_box(&points);
-        let height = pmax.y - pmin.y;
-        if height > last_height {
-            break;
+        let mut sky = Sky {
+            points,
+            lit: FxHashSet::default(),
+            pmin,
+            pmax,
+            max_size,
+        };
+        sky.update_lit();
+        sky
+    }
+
+    fn step(&mut self) {
+        step_points(&mut self.points);
+        let (pmin, pmax) = bounding_box(&self.points);
+        self.pmin = pmin;
+        self.pmax = pmax;
+        if self.fits() {
+            self.update_lit();
         }
-        last_height = height;
-        if height < 12 {
-            grid = render_grid(&points, pmin, pmax);
+    }
+
+    // Skips the frames where the points are still too spread out to draw. Once the bounding box
+    // stops shrinking the points will never fit, whether they're diverging or standing still.
+    fn skip_until_fits(&mut self) -> Result<(), Error> {
+        let mut last_area = i64::MAX;
+        while !self.fits() {
+            let area = area_at(&self.points, 0);
+            if area >= last_area {
+                return Err("Points never converge enough to fit on screen".into());
+            }
+            last_area = area;
+            self.step();
         }
+        Ok(())
+    }
 
-        step_points(&mut points);
+    fn update_lit(&mut self) {
+        self.lit = self.points.iter().map(|p| (p.x, p.y)).collect();
     }
 
-    if grid.is_empty() {
-        Err("Failed to find small grid to identify as containing a message".into())
-    } else {
-        Ok(grid)
+    fn fits(&self) -> bool {
+        let (width, height) = animate::Frame::size(self);
+        width <= self.max_size.0 && height <= self.max_size.1
     }
 }
 
-// Same basic idea but just keep count of which iteration ends up having the smallest grid height
-pub fn part2(input: &str) -> Result<u32, Error> {
-    let mut points = parse_input(input)?;
+impl animate::Frame for Sky {
+    fn size(&self) -> (usize, usize) {
+        (
+            (self.pmax.x - self.pmin.x + 1) as usize,
+            (self.pmax.y - self.pmin.y + 1) as usize,
+        )
+    }
 
-    let mut i = 0;
-    let mut last_height = MAX_I32;
-    loop {
-        let (pmin, pmax) = bounding_box(&points);
-        let height = pmax.y - pmin.y;
-        if height > last_height {
-            break;
+    fn cell(&self, x: usize, y: usize) -> (char, Color) {
+        let coord = (self.pmin.x + x as i32, self.pmin.y + y as i32);
+        if self.lit.contains(&coord) {
+            ('#', Color::Cyan)
+        } else {
+            ('.', Color::Default)
         }
-        last_height = height;
-
-        step_points(&mut points);
-        i += 1;
     }
 
-    Ok(i - 1)
+    // Keeps animating until the points spread back out beyond the screen
+    fn advance(&mut self) -> bool {
+        self.step();
+        self.fits()
+    }
 }
 
 lazy_static! {
@@ -219,6 +376,123 @@ position=<-3,  6> velocity=< 2, -1>
         assert_eq!(part2(INPUT).unwrap(), 3);
     }
 
+    // Builds points that spell out `art` (scaled up by `scale`) after `t` seconds
+    fn converging_input(art: &str, scale: i32, t: i32) -> String {
+        let mut input = String::new();
+        for (y, row) in art.lines().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                if c != '#' {
+                    continue;
+                }
+                for sy in 0..scale {
+                    for sx in 0..scale {
+                        let (x, y) = (x as i32 * scale + sx, y as i32 * scale + sy);
+                        // Fan the velocities out so the points only line up once
+                        let (dx, dy) = ((x * 7 + y * 3) % 5 - 2, (x * 3 + y * 7) % 5 - 2);
+                        let (dx, dy) = if dx == 0 && dy == 0 { (1, 1) } else { (dx, dy) };
+                        input += &format!(
+                            "position=<{}, {}> velocity=<{}, {}>\n",
+                            x - dx * t,
+                            y - dy * t,
+                            dx,
+                            dy
+                        );
+                    }
+                }
+            }
+        }
+        input
+    }
+
+    const MESSAGE: &str = r#"
+######..#....#
+#.......#....#
+#.......#....#
+#.......#....#
+#####...######
+#.......#....#
+#.......#....#
+#.......#....#
+#.......#....#
+######..#....#
+"#;
+
+    #[test]
+    fn test_decoded_message() {
+        let input = converging_input(MESSAGE.trim(), 1, 10_135);
+        assert_eq!(part1(&input).unwrap(), "EH");
+        assert_eq!(part2(&input).unwrap(), 10_135);
+    }
+
+    #[test]
+    fn test_tall_font() {
+        // Twice the size of the usual font, which can't be decoded so it's returned as art
+        let input = converging_input(MESSAGE.trim(), 2, 500);
+        assert_eq!(part2(&input).unwrap(), 500);
+        let art = part1(&input).unwrap();
+        assert_eq!(art.lines().count(), 20);
+        assert!(art.starts_with("############....##........##\n"));
+    }
+
+    #[test]
+    fn test_outlier() {
+        // The first two points meet at t=10, but the outlier passes them early, making a smaller
+        // box at t=7 than the seconds on either side of it
+        let input = "position=<0, 0> velocity=<0, 0>
+position=<20, 0> velocity=<-2, 0>
+position=<0, 20> velocity=<0, -3>";
+        let points = parse_input(input).unwrap();
+        assert_eq!(area_at(&points, 7), 14);
+        assert!(area_at(&points, 6) > 14 && area_at(&points, 8) > 14);
+        assert_eq!(area_at(&points, 10), 11);
+        assert_eq!(part2(input).unwrap(), 10);
+    }
+
+    #[test]
+    fn test_late_convergence() {
+        let input = "position=<-2000000000, 0> velocity=<2, 0>
+position=<2000000000, 0> velocity=<0, 0>";
+        assert_eq!(part2(input).unwrap(), 2_000_000_000);
+        assert_eq!(part1(input).unwrap(), "#\n");
+
+        let edge = [Point {
+            x: i32::MAX,
+            y: 0,
+            dx: 1,
+            dy: 0,
+        }];
+        assert!(points_at(&edge, 1).is_err());
+    }
+
+    #[test]
+    fn test_sky_frames() {
+        use crate::animate::Frame;
+
+        let mut sky = Sky::new(parse_input(INPUT).unwrap(), (12, 12));
+        assert!(!sky.fits());
+        sky.step();
+        sky.step();
+        sky.step();
+        assert_eq!(sky.size(), (10, 8));
+        assert_eq!(sky.cell(0, 0), ('#', Color::Cyan));
+        assert_eq!(sky.cell(1, 0), ('.', Color::Default));
+
+        let mut sky = Sky::new(parse_input(INPUT).unwrap(), (12, 12));
+        sky.skip_until_fits().unwrap();
+        assert_eq!(sky.size(), (10, 8));
+    }
+
+    #[test]
+    fn test_sky_never_fits() {
+        let stationary = "position=<0, 0> velocity=<0, 0>\nposition=<50, 50> velocity=<0, 0>";
+        let mut sky = Sky::new(parse_input(stationary).unwrap(), (12, 12));
+        assert!(sky.skip_until_fits().is_err());
+
+        let diverging = "position=<0, 0> velocity=<-1, 0>\nposition=<50, 0> velocity=<1, 0>";
+        let mut sky = Sky::new(parse_input(diverging).unwrap(), (12, 12));
+        assert!(sky.skip_until_fits().is_err());
+    }
+
     #[cfg_attr(feature = "bench", bench)]
     #[cfg(feature = "bench")]
     fn bench_part1(b: &mut Bencher) {
@@ -1,5 +1,5 @@
-use matrix::format::conventional::Conventional;
 use rayon::prelude::*;
+use crate::grid::{self, Grid};
 use crate::Error;
 
 // 2018 AoC Day 11 puzzle
@@ -19,8 +19,8 @@ fn power_level(x: u32, y: u32, sn: i32) -> i8 {
     (((y as i32 * rack_id + sn) * rack_id) / 100 % 10 - 5) as i8
 }
 
-fn build_grid(sn: i32) -> Conventional<i8> {
-    let mut grid = Conventional::new((WIDTH, HEIGHT));
+fn build_grid(sn: i32) -> Grid<i8> {
+    let mut grid = grid::new(WIDTH, HEIGHT);
     for i in 0..HEIGHT {
         for j in 0..WIDTH {
             let level = power_level(1 + j as u32, 1 + i as u32, sn);
@@ -30,7 +30,7 @@ fn build_grid(sn: i32) -> Conventional<i8> {
     grid
 }
 
-fn find_subgrid_power(grid: &Conventional<i8>, square_size: usize) -> SubGridPower {
+fn find_subgrid_power(grid: &Grid<i8>, square_size: usize) -> SubGridPower {
     let mut i_max = 0;
     let mut j_max = 0;
     let mut val_max: i32 = 0;
@@ -1,49 +1,77 @@
-use matrix::format::conventional::Conventional;
-use matrix::Element;
 use std::cmp::Ordering;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::ops::Add;
+use crate::animate::{self, Color};
+use crate::grid::{self, Grid, GridExt};
 use crate::Error;
 
 // 2018 AoC Day 13 puzzle
 // https://adventofcode.com/2018/day/13
 
-// turns out the pattern starts repeating just before the 100th generation
+// Upper bound on ticks so malformed tracks don't simulate forever
+const MAX_TICKS: usize = 1_000_000;
+
+// Set AOC_VERBOSE in the environment to log every cart event to stderr
+fn verbose() -> bool {
+    ::std::env::var_os("AOC_VERBOSE").is_some()
+}
+
 pub fn part1(input: &str) -> Result<String, Error> {
     let mut map = parse_input(input);
-    loop {
-        if let Some(coord) = map.tick() {
-            return Ok(format!("{},{}", coord.x, coord.y));
-        }
-    }
+    map.set_verbose(verbose());
+    let mut crash = None;
+    map.run_until(MAX_TICKS, |_, events| {
+        crash = events.iter().find_map(|evt| match evt {
+            Event::Crash { coord, .. } => Some(*coord),
+            _ => None,
+        });
+        crash.is_some()
+    })?;
+    let coord = crash.expect("run_until only returns Ok after a crash");
+    Ok(format!("{},{}", coord.x, coord.y))
 }
 
 pub fn part2(input: &str) -> Result<String, Error> {
     let mut map = parse_input(input);
-    // print(&map);
-    println!("{} cars", map.carts.len());
-    let mut prev;
-    for _i in 0..1_000_000 {
-        prev = map.clone();
-        let crashes = map.tick_remove_crashes();
-        // print(&map);
-        if crashes > 0 {
-            println!("Removed {} cars. {} remaining", crashes, map.carts.len());
-        }
-        if map.carts.len() <= 1 {
-            let coord = map.carts[0].coord;
-            println!("{:?}", prev.carts);
-            println!("{:?}", map.carts[0]);
-            map.tick();
-            println!("{:?}", map.carts[0]);
-            return Ok(format!("{},{}", coord.x, coord.y));
-        }
+    map.set_verbose(verbose());
+    map.run_until(MAX_TICKS, |map, _| map.carts.len() <= 1)?;
+    match map.carts.first() {
+        Some(cart) => Ok(format!("{},{}", cart.coord.x, cart.coord.y)),
+        None => Err("Every cart crashed, leaving no last cart".into()),
     }
-    unreachable!();
 }
 
+// Animates carts driving around the tracks until only one is left
+pub fn animate(input: &str, opts: &animate::Options) -> Result<(), Error> {
+    let mut animation = CartAnimation {
+        map: parse_input(input),
+        crashes: HashSet::new(),
+    };
+    animate::run(&mut animation, opts, &mut ::std::io::stdout())?;
+    Ok(())
+}
+
+// Everything that can happen to a cart during a single tick
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Event {
+    Move {
+        cart: usize,
+        from: Coordinate,
+        to: Coordinate,
+    },
+    Crash {
+        coord: Coordinate,
+        carts: (usize, usize),
+    },
+    Removal {
+        cart: usize,
+        coord: Coordinate,
+    },
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
 pub enum Cell {
+    #[default]
     None,
     NorthSouth,
     EastWest,
@@ -52,15 +80,6 @@ pub enum Cell {
     Intersection,
 }
 
-impl Element for Cell {
-    fn is_zero(&self) -> bool {
-        *self == Cell::None
-    }
-    fn zero() -> Self {
-        Cell::None
-    }
-}
-
 #[derive(Debug, Copy, Clone)]
 pub enum Direction {
     North,
@@ -125,9 +144,10 @@ impl Coordinate {
     }
 }
 
+// Carts move in reading order: top-to-bottom, then left-to-right
 impl Ord for Coordinate {
     fn cmp(&self, other: &Coordinate) -> Ordering {
-        ((self.y as u64) << 32 + self.x as u64).cmp(&((other.y as u64) << 32 + other.x as u64))
+        (self.y, self.x).cmp(&(other.y, other.x))
     }
 }
 
@@ -139,19 +159,19 @@ impl PartialOrd for Coordinate {
 
 #[derive(Debug, Copy, Clone)]
 pub struct Cart {
+    id: usize,
     direction: Direction,
     coord: Coordinate,
     next_intersection: Turn,
-    crashed: bool,
 }
 
 impl Cart {
-    fn new(x: u32, y: u32, direction: Direction) -> Cart {
+    fn new(id: usize, x: u32, y: u32, direction: Direction) -> Cart {
         Cart {
+            id,
             direction,
             coord: Coordinate { x, y },
             next_intersection: Turn::Left,
-            crashed: false,
         }
     }
 
@@ -199,17 +219,22 @@ impl Cart {
 
 #[derive(Debug, Clone)]
 pub struct Map {
-    grid: Conventional<Cell>,
+    grid: Grid<Cell>,
     carts: Vec<Cart>,
-    cart_lookup: HashSet<Coordinate>,
+    // Maps occupied coordinates to the id of the cart occupying it
+    cart_lookup: HashMap<Coordinate, usize>,
+    ticks: usize,
+    verbose: bool,
 }
 
 impl Map {
     fn new(width: usize, height: usize) -> Map {
         Map {
-            grid: Conventional::new((width, height)),
+            grid: grid::new(width, height),
             carts: Vec::new(),
-            cart_lookup: HashSet::new(),
+            cart_lookup: HashMap::new(),
+            ticks: 0,
+            verbose: false,
         }
     }
 
@@ -218,87 +243,137 @@ impl Map {
     }
 
     fn add_cart(&mut self, x: usize, y: usize, direction: Direction) {
-        let cart = Cart::new(x as u32, y as u32, direction);
-        self.cart_lookup.insert(cart.coord);
+        let cart = Cart::new(self.carts.len(), x as u32, y as u32, direction);
+        self.cart_lookup.insert(cart.coord, cart.id);
         self.carts.push(cart);
     }
 
-    // fn remove_carts(&mut self, x: usize, y: usize) {
-    //     let coord = Coordinate { x: x as u32, y: y as u32 };
-    //     self.cart_lookup.remove(&coord);
-    //     self.carts = self.carts.iter().filter(|c| c.coord != coord).map(|c| *c).collect();
-    // }
+    // Opt-in logging of every event to stderr as the simulation runs
+    pub fn set_verbose(&mut self, verbose: bool) {
+        self.verbose = verbose;
+    }
 }
 
 impl Map {
-    fn tick(&mut self) -> Option<Coordinate> {
-        // sort to make sure we move them in the right order
-        self.carts.sort_by(|a, b| a.coord.cmp(&b.coord));
+    // Advances every cart by one cell, in reading order, and returns what happened.
+    // Carts that collide are removed immediately, so a cart that gets hit before its turn doesn't move.
+    pub fn step(&mut self) -> Vec<Event> {
+        let mut events = Vec::new();
+        let mut removed = HashSet::new();
+
+        self.carts.sort_by_key(|cart| cart.coord);
         for cart in &mut self.carts {
+            if removed.contains(&cart.id) {
+                continue;
+            }
+
             // vacate the current coord and move to the new coord
-            self.cart_lookup.remove(&cart.coord);
-            cart.coord = cart.coord + cart.direction;
+            let from = cart.coord;
+            self.cart_lookup.remove(&from);
+            cart.coord = from + cart.direction;
+            events.push(Event::Move {
+                cart: cart.id,
+                from,
+                to: cart.coord,
+            });
+
+            match self.cart_lookup.remove(&cart.coord) {
+                Some(other) => {
+                    let coord = cart.coord;
+                    events.push(Event::Crash {
+                        coord,
+                        carts: (other, cart.id),
+                    });
+                    events.push(Event::Removal { cart: other, coord });
+                    events.push(Event::Removal {
+                        cart: cart.id,
+                        coord,
+                    });
+                    removed.insert(other);
+                    removed.insert(cart.id);
+                }
+                None => {
+                    self.cart_lookup.insert(cart.coord, cart.id);
 
-            // Insert the carts new coord into the lookup set, if this fails, then a collision occured
-            if !self.cart_lookup.insert(cart.coord) {
-                cart.crashed = true;
-                return Some(cart.coord);
+                    // Update the cart's direction based on the cell's path and previous driving turning history
+                    let cell_path = self.grid[cart.coord.tuple()];
+                    cart.update_heading(cell_path);
+                }
             }
+        }
+        self.carts.retain(|cart| !removed.contains(&cart.id));
+        self.ticks += 1;
 
-            // Update the cart's direction based on the cell's path and previous driving turning history
-            let cell_path = self.grid[cart.coord.tuple()];
-            cart.update_heading(cell_path);
+        if self.verbose {
+            for evt in &events {
+                eprintln!("tick {}: {:?}", self.ticks, evt);
+            }
         }
-        None
+        events
     }
 
-    // number of cars removed
-    fn tick_remove_crashes(&mut self) -> usize {
-        let mut crashes = HashSet::new();
-
-        // sort to make sure we move them in the right order
-        self.carts.sort_by(|a, b| a.coord.cmp(&b.coord));
-        for (_i, cart) in self.carts.iter_mut().enumerate() {
-            if crashes.contains(&cart.coord) {
-                continue;
+    // Steps until `done` returns true for the map and the events of the latest tick.
+    // Returns the number of ticks elapsed, or an error if `max_ticks` pass without finishing.
+    pub fn run_until<F>(&mut self, max_ticks: usize, mut done: F) -> Result<usize, Error>
+    where
+        F: FnMut(&Map, &[Event]) -> bool,
+    {
+        for _ in 0..max_ticks {
+            let events = self.step();
+            if done(self, &events) {
+                return Ok(self.ticks);
             }
+        }
+        Err(format!("Simulation did not finish within {} ticks", max_ticks).into())
+    }
+}
 
-            // vacate the current coord and move to the new coord
-            self.cart_lookup.remove(&cart.coord);
-            cart.coord = cart.coord + cart.direction;
-
-            // Insert the carts new coord into the lookup set, if this fails, then a collision occured
-            if self.cart_lookup.contains(&cart.coord) {
-                crashes.insert(cart.coord);
-                self.cart_lookup.remove(&cart.coord);
-            } else {
-                self.cart_lookup.insert(cart.coord);
-            }
+// Draws the tracks and carts, marking every crash site seen so far
+struct CartAnimation {
+    map: Map,
+    crashes: HashSet<Coordinate>,
+}
 
-            // Update the cart's direction based on the cell's path and previous driving turning history
-            let cell_path = self.grid[cart.coord.tuple()];
-            cart.update_heading(cell_path);
+impl animate::Frame for CartAnimation {
+    fn size(&self) -> (usize, usize) {
+        (self.map.grid.width(), self.map.grid.height())
+    }
+
+    fn cell(&self, x: usize, y: usize) -> (char, Color) {
+        let coord = Coordinate {
+            x: x as u32,
+            y: y as u32,
+        };
+        if let Some(id) = self.map.cart_lookup.get(&coord) {
+            let cart = self.map.carts.iter().find(|c| c.id == *id).unwrap();
+            let glyph = match cart.direction {
+                Direction::East => '>',
+                Direction::West => '<',
+                Direction::North => '^',
+                Direction::South => 'v',
+            };
+            return (glyph, Color::Green);
         }
+        if self.crashes.contains(&coord) {
+            return ('X', Color::Red);
+        }
+        match self.map.grid[(x, y)] {
+            Cell::EastWest => ('-', Color::Default),
+            Cell::NorthSouth => ('|', Color::Default),
+            Cell::CurveNwSe => ('\\', Color::Default),
+            Cell::CurveSwNe => ('/', Color::Default),
+            Cell::Intersection => ('+', Color::Yellow),
+            Cell::None => (' ', Color::Default),
+        }
+    }
 
-        if crashes.len() > 0 {
-            println!("CRASHES: {:?}", crashes);
-            println!(
-                "Removing: cars {:?}",
-                self.carts
-                    .iter()
-                    .filter(|c| crashes.contains(&c.coord))
-                    .map(|c| *c)
-                    .collect::<Vec<_>>()
-            );
+    fn advance(&mut self) -> bool {
+        for evt in self.map.step() {
+            if let Event::Crash { coord, .. } = evt {
+                self.crashes.insert(coord);
+            }
         }
-        self.carts = self
-            .carts
-            .iter()
-            .filter(|c| !crashes.contains(&c.coord))
-            .map(|c| *c)
-            .collect();
-
-        crashes.len() * 2
+        self.map.carts.len() > 1
     }
 }
 
@@ -332,43 +407,6 @@ fn parse_input(input: &str) -> Map {
     map
 }
 
-// fn print(map: &Map) {
-//     for y in 0..(map.grid.rows) {
-//         for x in 0..(map.grid.columns) {
-//             let coord = Coordinate {
-//                 x: x as u32,
-//                 y: y as u32,
-//             };
-//             if map.cart_lookup.contains(&coord) {
-//                 let cart = map.carts.iter().find(|&&c| c.coord == coord).unwrap();
-//                 print!(
-//                     "{}",
-//                     match cart.direction {
-//                         Direction::East => '>',
-//                         Direction::West => '<',
-//                         Direction::North => '^',
-//                         Direction::South => 'v',
-//                     }
-//                 )
-//             } else {
-//                 print!(
-//                     "{}",
-//                     match map.grid[coord.tuple()] {
-//                         Cell::EastWest => '-',
-//                         Cell::NorthSouth => '|',
-//                         Cell::CurveNwSe => '\\',
-//                         Cell::CurveSwNe => '/',
-//                         Cell::Intersection => '+',
-//                         Cell::None => ' ',
-//                     }
-//                 )
-//             }
-//         }
-//         println!("");
-//     }
-//     println!("")
-// }
-
 #[cfg(test)]
 mod test {
     use super::*;
@@ -404,6 +442,63 @@ mod test {
         assert_eq!(part2(input).unwrap(), "6,4");
     }
 
+    #[test]
+    fn test_step_events() {
+        let mut map = parse_input("->--<-");
+        let first = map.step();
+        assert_eq!(first.len(), 2);
+        assert!(first.iter().all(|evt| matches!(evt, Event::Move { .. })));
+
+        let second = map.step();
+        let crash = Coordinate { x: 3, y: 0 };
+        assert_eq!(
+            second,
+            vec![
+                Event::Move {
+                    cart: 0,
+                    from: Coordinate { x: 2, y: 0 },
+                    to: crash,
+                },
+                Event::Crash {
+                    coord: crash,
+                    carts: (1, 0),
+                },
+                Event::Removal {
+                    cart: 1,
+                    coord: crash,
+                },
+                Event::Removal {
+                    cart: 0,
+                    coord: crash,
+                },
+            ]
+        );
+        assert!(map.carts.is_empty());
+    }
+
+    #[test]
+    fn test_animation_frame() {
+        use crate::animate::Frame;
+
+        let mut animation = CartAnimation {
+            map: parse_input("+->--<-"),
+            crashes: HashSet::new(),
+        };
+        assert_eq!(animation.size(), (7, 1));
+        assert_eq!(animation.cell(0, 0), ('+', Color::Yellow));
+        assert_eq!(animation.cell(2, 0), ('>', Color::Green));
+        assert!(animation.advance());
+        assert_eq!(animation.cell(4, 0), ('<', Color::Green));
+        assert!(!animation.advance());
+        assert_eq!(animation.cell(4, 0), ('X', Color::Red));
+    }
+
+    #[test]
+    fn test_run_until_limit() {
+        let mut map = parse_input("->---");
+        assert!(map.run_until(2, |_, events| events.is_empty()).is_err());
+    }
+
     #[cfg_attr(feature = "bench", bench)]
     #[cfg(feature = "bench")]
     fn bench_part1(b: &mut Bencher) {
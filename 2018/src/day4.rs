@@ -5,104 +5,171 @@ use crate::Error;
 // 2018 AoC Day 4 puzzle
 // https://adventofcode.com/2018/day/4
 
-// Builds a Map
-fn guard_map(input: &str) -> Result<FxHashMap<u32, Vec<u64>>, Error> {
-    // Sort the lines since order is how we determine which guard a log entry refers to
-    let mut lines: Vec<_> = input.trim().lines().collect();
-    lines.sort();
-
-    // Parse the lines
-    let mut entries = Vec::new();
-    for line in lines {
-        let entry = parse_input(&line)?;
-        entries.push(entry);
-    }
+// Number of times a guard was asleep during each minute of the midnight hour
+type Histogram = [u32; 60];
 
-    // Find each Sleep->Wake cycle and turn it into a bitmap of the minutes asleep
-    // 60 minutes worth of bits fits nicely into a u64
-    let mut guard_map = FxHashMap::default();
-    let mut current_guard = 0_u32; // Guard 0 isn't really a valid starting point, but works as long as first record is Event::Wake
-    let mut sleep_min = 0;
-    for entry in entries {
-        match entry.evt {
-            Event::Start(id) => {
-                current_guard = id;
-                sleep_min = if entry.hr == 0 { entry.min } else { 0 };
-            }
-            Event::Sleep => {
-                sleep_min = if entry.hr == 0 { entry.min } else { 0 };
-            }
-            Event::Wake => {
-                let schedules = guard_map.entry(current_guard).or_insert_with(Vec::new);
-                let wake_min = if entry.hr == 0 { entry.min } else { 0 };
-                // println!("Guard {}: sleeping {}..{}", current_guard, sleep_min, wake_min);
-                let mut schedule = 0_u64;
-                for i in sleep_min..wake_min {
-                    schedule |= 1_u64 << i;
+// Per-guard sleep histograms, built from a chronologically validated event log
+struct SleepLog {
+    guards: FxHashMap<u32, Histogram>,
+}
+
+impl SleepLog {
+    fn new(input: &str) -> Result<SleepLog, Error> {
+        let mut entries = input
+            .trim()
+            .lines()
+            .map(parse_input)
+            .collect::<Result<Vec<_>, _>>()?;
+        // The log is shuffled, so order is recovered from the timestamps
+        entries.sort_by_key(|entry| entry.time);
+
+        let mut guards: FxHashMap<u32, Histogram> = FxHashMap::default();
+        let mut current_guard = None;
+        let mut asleep_since: Option<Timestamp> = None;
+        for entry in entries {
+            match entry.evt {
+                Event::Start(id) => {
+                    if let Some(since) = asleep_since {
+                        return Err(format!(
+                            "Shift changed at {} while asleep since {}",
+                            entry.time, since
+                        )
+                        .into());
+                    }
+                    current_guard = Some(id);
+                }
+                Event::Sleep => {
+                    if current_guard.is_none() {
+                        return Err(format!(
+                            "Fell asleep at {} before any guard began a shift",
+                            entry.time
+                        )
+                        .into());
+                    }
+                    if asleep_since.is_some() {
+                        return Err(
+                            format!("Fell asleep at {} while already asleep", entry.time).into(),
+                        );
+                    }
+                    entry.time.midnight_minute()?;
+                    asleep_since = Some(entry.time);
+                }
+                Event::Wake => {
+                    let since = asleep_since.take().ok_or_else(|| {
+                        format!("Woke up at {} without falling asleep", entry.time)
+                    })?;
+                    if !since.same_day(&entry.time) {
+                        return Err(format!(
+                            "Slept from {} into another day at {}",
+                            since, entry.time
+                        )
+                        .into());
+                    }
+                    // A guard is necessarily on shift here, since sleeping requires one
+                    let histogram = guards.entry(current_guard.unwrap()).or_insert([0; 60]);
+                    let (start, end) = (since.midnight_minute()?, entry.time.midnight_minute()?);
+                    for count in &mut histogram[start..end] {
+                        *count += 1;
+                    }
                 }
-                schedules.push(schedule);
             }
         }
+        if let Some(since) = asleep_since {
+            return Err(format!("Log ended while asleep since {}", since).into());
+        }
+
+        Ok(SleepLog { guards })
+    }
+
+    // Total minutes each guard spent asleep
+    fn total_asleep(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.guards
+            .iter()
+            .map(|(guard, histogram)| (*guard, histogram.iter().sum()))
+    }
+
+    // The minute each guard was most often asleep, along with how many times they were asleep then
+    fn sleepiest_minutes(&self) -> impl Iterator<Item = (u32, usize, u32)> + '_ {
+        self.guards.iter().map(|(guard, histogram)| {
+            let (minute, count) = sleepiest_minute(histogram);
+            (*guard, minute, count)
+        })
     }
-    Ok(guard_map)
+}
+
+fn sleepiest_minute(histogram: &Histogram) -> (usize, u32) {
+    // Reverse so the earliest minute wins ties
+    histogram
+        .iter()
+        .cloned()
+        .enumerate()
+        .rev()
+        .max_by_key(|&(_, count)| count)
+        .unwrap()
 }
 
 pub fn part1(input: &str) -> Result<u32, Error> {
-    let guard_map = guard_map(input)?;
+    let log = SleepLog::new(input)?;
 
-    // Find the guard that sleeps the most
-    let sleepy_guard = guard_map
-        .iter()
-        .max_by_key(|&(_k, v)| v.iter().map(|n| n.count_ones()).sum::<u32>())
-        .unwrap();
-
-    // Find the sleepiest minute for the sleepy guard
-    let mut sleepiest_minute = 0;
-    let mut sleepiest_count = 0;
-    for i in 0..60 {
-        let mut sum = 0;
-        for schedule in sleepy_guard.1 {
-            if schedule & (1_u64 << i) != 0 {
-                sum += 1
-            }
-        }
-        if sum > sleepiest_count {
-            sleepiest_count = sum;
-            sleepiest_minute = i;
-        }
-    }
+    // Find the guard that sleeps the most, then their sleepiest minute
+    let (guard, _) = log
+        .total_asleep()
+        .max_by_key(|&(guard, total)| (total, guard))
+        .ok_or("No guard ever fell asleep")?;
+    let (minute, _) = sleepiest_minute(&log.guards[&guard]);
 
-    Ok(sleepy_guard.0 * sleepiest_minute)
+    Ok(guard * minute as u32)
 }
 
 pub fn part2(input: &str) -> Result<u32, Error> {
-    let guard_map = guard_map(input)?;
+    let log = SleepLog::new(input)?;
 
     // Find the sleepiest minute among all guards
-    let mut sleepiest_minute = 0;
-    let mut sleepiest_count = 0;
-    let mut sleepiest_guard = 0;
-    for (guard, schedules) in guard_map {
-        for i in 0..60 {
-            let mut sum = 0;
-            for schedule in &schedules {
-                if schedule & (1_u64 << i) != 0 {
-                    sum += 1
-                }
-            }
-            if sum > sleepiest_count {
-                sleepiest_count = sum;
-                sleepiest_minute = i;
-                sleepiest_guard = guard
-            }
-        }
-    }
+    let (guard, minute, _) = log
+        .sleepiest_minutes()
+        .max_by_key(|&(guard, _, count)| (count, guard))
+        .ok_or("No guard ever fell asleep")?;
 
-    Ok(sleepiest_guard * sleepiest_minute)
+    Ok(guard * minute as u32)
 }
 
 lazy_static! {
-    static ref RE: Regex = Regex::new(r"^\[\d+-(\d+)-(\d+) (\d+):(\d+)\] (.+)$").unwrap();
+    static ref RE: Regex = Regex::new(r"^\[(\d+)-(\d+)-(\d+) (\d+):(\d+)\] (.+)$").unwrap();
+    static ref GUARD_RE: Regex = Regex::new(r"^Guard #(\d+) begins shift$").unwrap();
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct Timestamp {
+    // Field order matters, so the derived ordering is chronological
+    year: u16,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+}
+
+impl Timestamp {
+    fn same_day(&self, other: &Timestamp) -> bool {
+        (self.year, self.month, self.day) == (other.year, other.month, other.day)
+    }
+
+    // Guards only sleep during the midnight hour, so only the minute matters
+    fn midnight_minute(&self) -> Result<usize, Error> {
+        if self.hour != 0 {
+            return Err(format!("Sleep event at {} is outside the midnight hour", self).into());
+        }
+        Ok(self.minute as usize)
+    }
+}
+
+impl ::std::fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        write!(
+            f,
+            "{}-{:02}-{:02} {:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute
+        )
+    }
 }
 
 #[derive(Debug)]
@@ -114,33 +181,35 @@ enum Event {
 
 #[derive(Debug)]
 struct Entry {
-    mo: u8,
-    day: u8,
-    hr: u8,
-    min: u8,
+    time: Timestamp,
     evt: Event,
 }
 
 fn parse_input(input: &str) -> Result<Entry, Error> {
     let caps = RE
         .captures(input)
-        .ok_or_else(|| format!("Claim could not be parsed: {}", input))?;
+        .ok_or_else(|| format!("Log entry could not be parsed: {}", input))?;
 
-    let evt = match caps[5].trim() {
+    let evt = match caps[6].trim() {
         "falls asleep" => Event::Sleep,
         "wakes up" => Event::Wake,
         msg => {
-            let guard = msg.split(' ').nth(1).unwrap()[1..].parse()?;
-            Event::Start(guard)
+            let guard = GUARD_RE
+                .captures(msg)
+                .ok_or_else(|| format!("Unknown log event: {}", msg))?;
+            Event::Start(guard[1].parse()?)
         }
     };
 
     Ok(Entry {
-        mo: caps[1].parse()?,
-        day: caps[2].parse()?,
-        hr: caps[3].parse()?,
-        min: caps[4].parse()?,
-        evt: evt,
+        time: Timestamp {
+            year: caps[1].parse()?,
+            month: caps[2].parse()?,
+            day: caps[3].parse()?,
+            hour: caps[4].parse()?,
+            minute: caps[5].parse()?,
+        },
+        evt,
     })
 }
 
@@ -181,6 +250,44 @@ mod test {
         assert_eq!(part2(INPUT).unwrap(), 4455);
     }
 
+    #[test]
+    fn test_histograms() {
+        let log = SleepLog::new(INPUT).unwrap();
+        let histogram = log.guards[&10];
+        assert_eq!(histogram[24], 2);
+        assert_eq!(histogram[4], 0);
+        assert_eq!(histogram[5], 1);
+        assert_eq!(histogram.iter().sum::<u32>(), 50);
+        assert_eq!(sleepiest_minute(&log.guards[&99]), (45, 3));
+    }
+
+    #[test]
+    fn test_unsorted_log() {
+        let mut lines: Vec<_> = INPUT.trim().lines().collect();
+        lines.reverse();
+        assert_eq!(part1(&lines.join("\n")).unwrap(), 240);
+    }
+
+    #[test]
+    fn test_invalid_logs() {
+        let no_guard = "[1518-11-01 00:05] falls asleep";
+        assert!(SleepLog::new(no_guard).is_err());
+
+        let no_sleep = "[1518-11-01 00:00] Guard #10 begins shift\n[1518-11-01 00:25] wakes up";
+        assert!(SleepLog::new(no_sleep).is_err());
+
+        let still_asleep =
+            "[1518-11-01 00:00] Guard #10 begins shift\n[1518-11-01 00:05] falls asleep";
+        assert!(SleepLog::new(still_asleep).is_err());
+
+        let not_midnight = "[1518-11-01 23:50] Guard #10 begins shift\n\
+                            [1518-11-01 23:55] falls asleep\n\
+                            [1518-11-02 00:10] wakes up";
+        assert!(SleepLog::new(not_midnight).is_err());
+
+        assert!(SleepLog::new("[1518-11-01 00:00] Guard #10 naps").is_err());
+    }
+
     #[cfg_attr(feature = "bench", bench)]
     #[cfg(feature = "bench")]
     fn bench_part1(b: &mut Bencher) {
@@ -1,5 +1,6 @@
 use linked_list::LinkedList;
 use rayon::prelude::*;
+use std::io::{BufReader, Read};
 use crate::Error;
 
 // 2018 AoC Day 5 puzzle
@@ -10,7 +11,7 @@ const CASE_DIFF: u8 = 32;
 
 // Helper to identify same character with opposite case
 fn reacts(a: u8, b: u8) -> bool {
-    a == b + CASE_DIFF || b == a + CASE_DIFF
+    a.abs_diff(b) == CASE_DIFF && a.is_ascii_alphabetic() && b.is_ascii_alphabetic()
 }
 
 // This version uses a linked list with a cursor to actually remove matching elements
@@ -47,8 +48,42 @@ fn chain_react_counter(list: &mut LinkedList<u8>) -> usize {
     list.iter().count()
 }
 
+// Reacts units as they arrive, keeping the surviving polymer on a stack. Each new unit either
+// annihilates the unit on top of the stack or gets pushed, so the whole reaction is a single pass.
+fn reduce<I: IntoIterator<Item = u8>>(units: I) -> Vec<u8> {
+    let mut stack = Vec::new();
+    for unit in units {
+        react(&mut stack, unit);
+    }
+    stack
+}
+
+// Adds one unit to a reduced polymer, annihilating the top of the stack if the two react
+fn react(stack: &mut Vec<u8>, unit: u8) {
+    match stack.last() {
+        Some(&top) if reacts(top, unit) => {
+            stack.pop();
+        }
+        _ => stack.push(unit),
+    }
+}
+
+// Fully reacts a polymer read from a stream, ignoring any whitespace (like a trailing newline).
+// Each byte is reacted as soon as it's read, so only the surviving units are ever held in memory.
+pub fn reduce_stream<R: Read>(input: R) -> Result<Vec<u8>, Error> {
+    let mut stack = Vec::new();
+    for byte in BufReader::new(input).bytes() {
+        let byte = byte?;
+        if !byte.is_ascii_whitespace() {
+            react(&mut stack, byte);
+        }
+    }
+    Ok(stack)
+}
+
 // Instead of actually removing elements, this uses a more compact byte slice marking removed elements, and is experimentally much faster
 // which goes to show that even when you think a LinkedList is the solution, you should benchmark it against a vector
+#[allow(dead_code)]
 fn fast_chain_react_counter(input: &mut [u8]) -> usize {
     let mut count = input.len();
     let mut prev = 0;
@@ -79,11 +114,30 @@ fn fast_chain_react_counter(input: &mut [u8]) -> usize {
 }
 
 pub fn part1(input: &str) -> Result<usize, Error> {
-    let mut bytes = input.trim().as_bytes().to_owned();
-    Ok(fast_chain_react_counter(&mut bytes))
+    Ok(reduce_stream(input.as_bytes())?.len())
 }
 
+// Removing a unit type can't stop any reaction that already happened from happening again, so each
+// removal pass can start from the once-reduced polymer instead of the much longer original.
 pub fn part2(input: &str) -> Result<usize, Error> {
+    let polymer = reduce_stream(input.as_bytes())?;
+
+    let min = (b'A'..(b'Z' + 1))
+        .map(|c| {
+            let units = polymer
+                .iter()
+                .cloned()
+                .filter(|b| c % CASE_DIFF != b % CASE_DIFF);
+            reduce(units).len()
+        })
+        .min()
+        .unwrap();
+    Ok(min)
+}
+
+// The previous approach, which reacts the full input from scratch for every removed unit type
+#[allow(dead_code)]
+fn part2_from_scratch(input: &str) -> Result<usize, Error> {
     let bytes = input.trim().as_bytes().to_owned();
 
     // Let rayon iterate over each character in parallel
@@ -119,6 +173,38 @@ mod test {
         assert_eq!(part1("zaAcdeEDCZ").unwrap(), 0);
     }
 
+    #[test]
+    fn test_reduce_stream() {
+        let reduced = reduce_stream("dabAcCaCBAcCcaDA\n".as_bytes()).unwrap();
+        assert_eq!(reduced, b"dabCBAcaDA");
+        assert_eq!(reduce_stream(&b""[..]).unwrap(), b"");
+    }
+
+    #[test]
+    fn test_reacts() {
+        assert!(reacts(b'a', b'A'));
+        assert!(reacts(b'Z', b'z'));
+        assert!(!reacts(b'a', b'a'));
+        assert!(!reacts(b'a', b'B'));
+        // Bytes 32 apart that aren't letters, including ones that would overflow a u8 sum
+        assert!(!reacts(b'@', b'`'));
+        assert!(!reacts(b'!', b'A'));
+        assert!(!reacts(192, 224));
+        assert!(!reacts(255, 250));
+    }
+
+    #[test]
+    fn test_previous_counters() {
+        let input = "dabAcCaCBAcCcaDA";
+        assert_eq!(
+            fast_chain_react_counter(&mut input.as_bytes().to_owned()),
+            10
+        );
+        let mut list: LinkedList<u8> = input.bytes().collect();
+        assert_eq!(chain_react_counter(&mut list), 10);
+        assert_eq!(part2_from_scratch(input).unwrap(), 4);
+    }
+
     #[test]
     fn test_part2() {
         assert_eq!(part2("dabAcCaCBAcCcaDA").unwrap(), 4);
@@ -137,4 +223,28 @@ mod test {
         let input = ::std::fs::read_to_string("inputs/day-5.txt").expect("Unable to open file");
         b.iter(|| part2(&input).unwrap());
     }
+
+    #[cfg_attr(feature = "bench", bench)]
+    #[cfg(feature = "bench")]
+    fn bench_part1_marking(b: &mut Bencher) {
+        let input = ::std::fs::read_to_string("inputs/day-5.txt").expect("Unable to open file");
+        b.iter(|| fast_chain_react_counter(&mut input.trim().as_bytes().to_owned()));
+    }
+
+    #[cfg_attr(feature = "bench", bench)]
+    #[cfg(feature = "bench")]
+    fn bench_part1_linked_list(b: &mut Bencher) {
+        let input = ::std::fs::read_to_string("inputs/day-5.txt").expect("Unable to open file");
+        b.iter(|| {
+            let mut list: LinkedList<u8> = input.trim().bytes().collect();
+            chain_react_counter(&mut list)
+        });
+    }
+
+    #[cfg_attr(feature = "bench", bench)]
+    #[cfg(feature = "bench")]
+    fn bench_part2_from_scratch(b: &mut Bencher) {
+        let input = ::std::fs::read_to_string("inputs/day-5.txt").expect("Unable to open file");
+        b.iter(|| part2_from_scratch(&input).unwrap());
+    }
 }
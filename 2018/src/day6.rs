@@ -1,118 +1,104 @@
 use regex::Regex;
-use std::cmp::{max, min};
-use std::u32::MAX as MAX_U32;
+use crate::grid::GridExt;
+use crate::svg::Svg;
+use crate::voronoi::{self, Owner, Voronoi};
 use crate::Error;
 
 // 2018 AoC Day 6 puzzle
 // https://adventofcode.com/2018/day/6
 
-fn manhattan_distance(a: Point, b: Point) -> u32 {
-    max(a.x, b.x) - min(a.x, b.x) + max(a.y, b.y) - min(a.y, b.y)
-}
-
-// Finds the min and max points that serve as the bounding box that contains all other points
-fn bounding_box(points: &[Point]) -> (Point, Point) {
-    let mut iter = points.iter();
-    let p = iter.next().unwrap();
-    let mut pmin = *p;
-    let mut pmax = *p;
-    for p in iter {
-        pmin.x = min(pmin.x, p.x);
-        pmax.x = max(pmax.x, p.x);
-        pmin.y = min(pmin.y, p.y);
-        pmax.y = max(pmax.y, p.y);
-    }
-    (pmin, pmax)
-}
-
-pub fn part1(input: &str) -> Result<u32, Error> {
+pub fn part1(input: &str) -> Result<usize, Error> {
     let points = parse_input(input)?;
-    let (pmin, pmax) = bounding_box(&points);
-
-    let mut regions = vec![0; points.len()];
-
-    for y in (pmin.y)..(pmax.y + 1) {
-        for x in (pmin.x)..(pmax.x + 1) {
-            let mut min_index = None;
-            let mut min_distance = 0;
-            let grid_point = Point { x, y };
-            for (i, p) in points.iter().enumerate() {
-                let distance = manhattan_distance(*p, grid_point);
-                if i == 0 || distance < min_distance {
-                    min_index = Some(i);
-                    min_distance = distance;
-                } else if distance == min_distance {
-                    min_index = None;
-                }
-            }
-            // print!("{}, ", min_index);
-            if let Some(index) = min_index {
-                if x == pmin.x || y == pmin.y || x == pmax.x || y == pmax.y {
-                    regions[index] = MAX_U32;
-                } else if regions[index] < MAX_U32 {
-                    regions[index] += 1;
-                }
-            }
-        }
-        // println!("");
-    }
-
-    // println!("{:?}", regions);
-    let largest = regions
+    let largest = Voronoi::new(&points)
+        .areas()
         .into_iter()
-        .filter(|x| *x != MAX_U32)
+        .flatten()
         .max()
-        .expect("no max found");
+        .ok_or("Every region is infinite")?;
 
     Ok(largest)
 }
 
-pub fn part2(input: &str, total_distance_bound: u32) -> Result<u32, Error> {
+// Draws each cell of the bounding box in the color of its closest point, leaving ties white.
+// Infinite regions (those reaching the edge of the bounding box) are washed out and outlined in red.
+pub fn svg(input: &str) -> Result<Svg, Error> {
+    const SIZE: f64 = 10.0;
     let points = parse_input(input)?;
-    let (pmin, pmax) = bounding_box(&points);
-
-    let mut region_size = 0;
-    for y in (pmin.y)..(pmax.y + 1) {
-        for x in (pmin.x)..(pmax.x + 1) {
-            // Add up all the distances
-            let grid_point = Point { x, y };
-            let total_distance: u32 = points
+    let voronoi = Voronoi::new(&points);
+    let infinite = voronoi.infinite();
+    let owners = voronoi.owners();
+    let (x0, y0) = voronoi.origin();
+    let (width, height) = (owners.width(), owners.height());
+
+    let mut svg = Svg::new(width as f64 * SIZE, height as f64 * SIZE);
+    for y in 0..height {
+        for x in 0..width {
+            let fill = match owners[(x, y)] {
+                Owner::Site(index) if infinite[index] => {
+                    format!("hsl({}, 20%, 85%)", index * 137 % 360)
+                }
+                Owner::Site(index) => format!("hsl({}, 70%, 65%)", index * 137 % 360),
+                _ => "white".to_string(),
+            };
+            let coord = (x0 + x as i64, y0 + y as i64);
+            let label = points
                 .iter()
-                .map(|p| manhattan_distance(*p, grid_point))
-                .sum();
+                .position(|p| *p == coord)
+                .map(|index| index.to_string());
+            svg.cell(x, y, SIZE, &fill, label.as_deref());
+        }
+    }
 
-            if total_distance < total_distance_bound {
-                region_size += 1;
+    // Outline the edge cells of infinite regions so they stand out
+    let edge = |x: f64, y: f64| (x * SIZE, y * SIZE);
+    let (w, h) = (width as f64, height as f64);
+    for ((x, y), owner) in owners.indexed_iter() {
+        if x != 0 && y != 0 && x != width - 1 && y != height - 1 {
+            continue;
+        }
+        if let Owner::Site(index) = owner {
+            if infinite[*index] {
+                let (x, y) = (x as f64, y as f64);
+                svg.polygon(
+                    &[
+                        edge(x, y),
+                        edge(x + 1.0, y),
+                        edge(x + 1.0, y + 1.0),
+                        edge(x, y + 1.0),
+                    ],
+                    "none",
+                    "red",
+                );
             }
         }
     }
+    svg.polygon(
+        &[edge(0.0, 0.0), edge(w, 0.0), edge(w, h), edge(0.0, h)],
+        "none",
+        "black",
+    );
+    Ok(svg)
+}
 
-    Ok(region_size)
+pub fn part2(input: &str, total_distance_bound: u64) -> Result<usize, Error> {
+    let points = parse_input(input)?;
+    Ok(voronoi::region_within(&points, total_distance_bound))
 }
 
 lazy_static! {
     static ref RE: Regex = Regex::new(r"^(\d+), (\d+)$").unwrap();
 }
 
-#[derive(Debug, Copy, Clone)]
-struct Point {
-    x: u32,
-    y: u32,
-}
-
-fn parse_input(input: &str) -> Result<Vec<Point>, Error> {
+fn parse_input(input: &str) -> Result<Vec<(i64, i64)>, Error> {
     input.trim().lines().map(parse_line).collect()
 }
 
-fn parse_line(input: &str) -> Result<Point, Error> {
+fn parse_line(input: &str) -> Result<(i64, i64), Error> {
     let caps = RE
         .captures(input)
         .ok_or_else(|| format!("Claim could not be parsed: {}", input))?;
 
-    Ok(Point {
-        x: caps[1].parse()?,
-        y: caps[2].parse()?,
-    })
+    Ok((caps[1].parse()?, caps[2].parse()?))
 }
 
 #[cfg(test)]
@@ -139,6 +125,23 @@ mod test {
     #[test]
     fn test_part2() {
         assert_eq!(part2(INPUT, 32).unwrap(), 16);
+        // With a loose bound the region spills out of the bounding box
+        assert_eq!(part2("0, 0\n2, 0", 6).unwrap(), 11);
+    }
+
+    #[test]
+    fn test_svg() {
+        let doc = svg(INPUT).unwrap().to_string();
+        assert!(doc.contains(r#"viewBox="0 0 80 90""#));
+        // Point 0 at (1, 1) is the top-left corner of an infinite region
+        assert!(
+            doc.contains(r#"<rect x="0" y="0" width="10" height="10" fill="hsl(0, 20%, 85%)"/>"#)
+        );
+        assert!(doc.contains(">0</text>"));
+        // Point 4 at (5, 5) is the largest finite region
+        assert!(doc
+            .contains(r#"<rect x="40" y="40" width="10" height="10" fill="hsl(188, 70%, 65%)"/>"#));
+        assert_eq!(doc.matches("<text").count(), 6);
     }
 
     #[cfg_attr(feature = "bench", bench)]
@@ -1,6 +1,7 @@
 use daggy::{Dag, NodeIndex, Walker};
 use fxhash::FxHashMap;
 use regex::Regex;
+use crate::scheduler::{Schedule, Scheduler};
 use crate::Error;
 
 // 2018 AoC Day 7 puzzle
@@ -41,54 +42,34 @@ pub fn part1(input: &str) -> Result<String, Error> {
     Ok(String::from_utf8_lossy(&*ordered_steps).to_string())
 }
 
-#[derive(Debug)]
-struct Worker {
-    step: Option<u8>,
-    remaining: u32,
-}
-
 // This function convers the ascii byte value of a capital letter to the cardinal
 // e.g. 'A' == 1, 'B' ==2, etc...
 fn ascii_cardinal(step: u8) -> u32 {
     step as u32 - 64
 }
 
-pub fn part2(input: &str, worker_count: u32, base_time: u32) -> Result<u32, Error> {
-    let mut step_tracker = StepTracker::new(input)?;
+fn build_scheduler(input: &str, base_time: u32) -> Result<Scheduler<u8>, Error> {
+    let edges: Vec<Edge> = input
+        .trim()
+        .lines()
+        .map(parse_line)
+        .collect::<Result<_, _>>()?;
 
-    let mut second = 0;
-    let mut workers = Vec::new();
-    for _ in 0..worker_count {
-        workers.push(Worker {
-            step: None,
-            remaining: 0,
-        })
+    let mut scheduler = Scheduler::new();
+    for edge in edges {
+        scheduler.add_task(edge.prev, base_time + ascii_cardinal(edge.prev));
+        scheduler.add_task(edge.next, base_time + ascii_cardinal(edge.next));
+        scheduler.add_dependency(edge.prev, edge.next);
     }
+    Ok(scheduler)
+}
 
-    while !step_tracker.is_complete() {
-        for worker in &mut workers {
-            if let Some(step) = worker.step {
-                if worker.remaining > 0 {
-                    worker.remaining -= 1
-                } else if worker.remaining == 0 {
-                    step_tracker.complete_step(step);
-                    worker.step = None;
-                }
-            }
-        }
-        for worker in &mut workers {
-            if worker.step.is_none() {
-                if let Some(step) = step_tracker.get_next_step() {
-                    worker.step = Some(step);
-                    worker.remaining = base_time + ascii_cardinal(step) - 1;
-                }
-            }
-        }
-
-        second += 1;
-    }
+pub fn schedule(input: &str, worker_count: usize, base_time: u32) -> Result<Schedule<u8>, Error> {
+    build_scheduler(input, base_time)?.run(worker_count)
+}
 
-    Ok(second - 1)
+pub fn part2(input: &str, worker_count: usize, base_time: u32) -> Result<u32, Error> {
+    Ok(schedule(input, worker_count, base_time)?.makespan)
 }
 
 // Step tracker contains a DAG and walks it in the order of lowest node value of nodes already reached
@@ -137,10 +118,6 @@ impl StepTracker {
         })
     }
 
-    fn is_complete(&self) -> bool {
-        self.ready_map.is_empty() && self.pending_map.is_empty()
-    }
-
     fn get_next_step(&mut self) -> Option<u8> {
         if let Some(step) = self.ready_map.iter().map(|(step, _)| *step).min() {
             let val = self.ready_map.remove(&step).unwrap();
@@ -225,6 +202,27 @@ Step F must be finished before step E can begin.
         assert_eq!(part2(INPUT, 2, 0).unwrap(), 15);
     }
 
+    #[test]
+    fn test_schedule() {
+        let schedule = schedule(INPUT, 2, 0).unwrap();
+        let timeline: Vec<_> = schedule
+            .assignments
+            .iter()
+            .map(|a| (a.task as char, a.worker, a.start, a.end))
+            .collect();
+        assert_eq!(
+            timeline,
+            vec![
+                ('C', 0, 0, 3),
+                ('A', 0, 3, 4),
+                ('F', 1, 3, 9),
+                ('B', 0, 4, 6),
+                ('D', 0, 6, 10),
+                ('E', 0, 10, 15),
+            ]
+        );
+    }
+
     #[cfg_attr(feature = "bench", bench)]
     #[cfg(feature = "bench")]
     fn bench_part1(b: &mut Bencher) {
@@ -1,97 +1,61 @@
+use std::convert::TryFrom;
+use crate::tree::Tree;
 use crate::Error;
 
 // 2018 AoC Day 8 puzzle
 // https://adventofcode.com/2018/day/8
 
-struct RecursiveSum {
-    sum: u32,
-    len: usize,
-}
-
-fn sum_meta_count(list: &[u32], node_count: usize) -> RecursiveSum {
-    let mut sum = 0;
+// Each node holds its metadata entries
+type License = Tree<Vec<u32>>;
 
-    let mut i: usize = 0;
-    for _j in 0..node_count {
-        let child_count = list[i] as usize;
-        let meta_count = list[i + 1] as usize;
-        i += 2;
-
-        if child_count > 0 {
-            let res = sum_meta_count(&list[i..], child_count);
-            i += res.len;
-            sum += res.sum;
-        }
-
-        if meta_count > 0 {
-            sum += list[i..(i + meta_count)].iter().sum::<u32>();
-            i += meta_count;
-        }
-    }
-
-    RecursiveSum { sum, len: i }
+pub fn part1(input: &str) -> Result<u32, Error> {
+    let tree = parse_input(input)?;
+    Ok(tree.fold(|meta, children: &[u32]| meta.iter().sum::<u32>() + children.iter().sum::<u32>()))
 }
 
-struct RecursiveVals {
-    vals: Vec<u32>,
-    len: usize,
+pub fn part2(input: &str) -> Result<u32, Error> {
+    let tree = parse_input(input)?;
+    Ok(tree.fold(|meta, children: &[u32]| {
+        if children.is_empty() {
+            return meta.iter().sum();
+        }
+        // Metadata entries are 1-based child references, and missing children are worth nothing
+        meta.iter()
+            .filter_map(|m| children.get((*m as usize).wrapping_sub(1)))
+            .sum()
+    }))
 }
 
-fn get_node_values(list: &[u32], node_count: usize) -> RecursiveVals {
-    let mut i: usize = 0;
-    let mut node_vals = vec![0; node_count];
-    for j in 0..node_count {
-        let child_count = list[i] as usize;
-        let meta_count = list[i + 1] as usize;
-        i += 2;
-
-        let mut child_vals = vec![0; child_count];
-        if child_count > 0 {
-            let res = get_node_values(&list[i..], child_count);
-            i += res.len;
-            child_vals = res.vals;
+// Builds the tree in a single pass over the numbers. Since metadata follows a node's children, the
+// stack holds the nodes whose children are still being read along with the counts from their header.
+fn parse_input(input: &str) -> Result<License, Error> {
+    let mut nums = input.split_whitespace().map(str::parse::<usize>);
+    let mut next = move || -> Result<usize, Error> {
+        match nums.next() {
+            Some(n) => Ok(n?),
+            None => Err("Unexpected end of input".into()),
         }
-
-        if child_count > 0 {
-            for k in i..(i + meta_count) {
-                if list[k] == 0 {
-                    continue;
-                }
-                let node_idx = list[k] as usize - 1;
-                if let Some(meta) = child_vals.get(node_idx) {
-                    node_vals[j] += meta;
-                }
+    };
+
+    let mut tree = Tree::new(Vec::new());
+    let mut stack = vec![(tree.root(), next()?, next()?)];
+    while let Some((id, children_left, meta_count)) = stack.pop() {
+        if children_left > 0 {
+            stack.push((id, children_left - 1, meta_count));
+            let child = tree.add_child(id, Vec::new());
+            stack.push((child, next()?, next()?));
+        } else {
+            for _ in 0..meta_count {
+                let meta = u32::try_from(next()?).map_err(|_| "Metadata entry is too large")?;
+                tree.data_mut(id).push(meta);
             }
-            i += meta_count;
-        } else if meta_count > 0 {
-            node_vals[j] = list[i..(i + meta_count)].iter().sum::<u32>();
-            i += meta_count;
         }
     }
 
-    RecursiveVals {
-        vals: node_vals,
-        len: i,
+    if next().is_ok() {
+        return Err("Unexpected numbers after the root node".into());
     }
-}
-
-pub fn part1(input: &str) -> Result<u32, Error> {
-    let elements = parse_input(&input)?;
-    Ok(sum_meta_count(&elements, 1).sum)
-}
-
-pub fn part2(input: &str) -> Result<u32, Error> {
-    let elements = parse_input(&input)?;
-    Ok(get_node_values(&elements, 1).vals[0])
-}
-
-fn parse_input(input: &str) -> Result<Vec<u32>, Error> {
-    let vec = input
-        .trim()
-        .split(' ')
-        .map(str::parse)
-        .collect::<Result<_, _>>()?;
-    Ok(vec)
+    Ok(tree)
 }
 
 #[cfg(test)]
@@ -113,6 +77,26 @@ mod test {
         assert_eq!(part2(INPUT).unwrap(), 66);
     }
 
+    #[test]
+    fn test_parse_input() {
+        let tree = parse_input(INPUT).unwrap();
+        assert_eq!(tree.len(), 4);
+        assert_eq!(tree.node(tree.root()).data, vec![1, 1, 2]);
+        assert!(parse_input("1 1 0 1").is_err());
+        assert!(parse_input("0 1 1 5").is_err());
+        assert!(parse_input("0 1 4294967296").is_err());
+        assert_eq!(part1("0 1 4294967295").unwrap(), u32::MAX);
+    }
+
+    #[test]
+    fn test_deep_input() {
+        // A chain of nodes each with one child and a single metadata entry of 1
+        let depth = 200_000;
+        let input = "1 1 ".repeat(depth) + "0 1 1" + &" 1".repeat(depth);
+        assert_eq!(part1(&input).unwrap(), depth as u32 + 1);
+        assert_eq!(part2(&input).unwrap(), 1);
+    }
+
     #[cfg_attr(feature = "bench", bench)]
     #[cfg(feature = "bench")]
     fn bench_part1(b: &mut Bencher) {
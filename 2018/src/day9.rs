@@ -1,10 +1,12 @@
 use linked_list::{Cursor, LinkedList};
 use regex::Regex;
+use crate::ring::Ring;
 use crate::Error;
 
 // 2018 AoC Day 9 puzzle
 // https://adventofcode.com/2018/day/9
 
+// Original LinkedList-based version, kept around to benchmark against the Ring version below.
 // The cursor in the LinkedList imported puts an "ghost" element between head and tail
 // This alternative cursor automatically skips the ghost to act like a true circular linked list
 struct RingCursor<'a, T: 'a> {
@@ -37,7 +39,8 @@ impl<'a, T> RingCursor<'a, T> {
     }
 }
 
-fn high_score(players: usize, last_marble: u32) -> u32 {
+#[allow(dead_code)]
+fn linked_list_high_score(players: usize, last_marble: u32) -> u32 {
     let mut scores = vec![0; players];
 
     let mut circle = LinkedList::new();
@@ -61,6 +64,29 @@ fn high_score(players: usize, last_marble: u32) -> u32 {
     scores.into_iter().max().unwrap()
 }
 
+// Rotating a VecDeque moves contiguous memory instead of walking node-by-node,
+// which makes the 100x marbles of part 2 run in a fraction of the time
+fn high_score(players: usize, last_marble: u32) -> u32 {
+    let mut scores = vec![0; players];
+
+    let mut circle = Ring::with_capacity(last_marble as usize + 1);
+    circle.insert(0);
+
+    for i in 1..(last_marble + 1) {
+        if i % 23 == 0 {
+            let player = (i as usize - 1) % scores.len();
+            scores[player] += i;
+            circle.seek_backward(7);
+            scores[player] += circle.remove().expect("Circle is never empty");
+        } else {
+            circle.seek_forward(2);
+            circle.insert(i);
+        }
+    }
+
+    scores.into_iter().max().unwrap()
+}
+
 pub fn part1(input: &str) -> Result<u32, Error> {
     let Config {
         players,
@@ -115,6 +141,13 @@ mod test {
         assert_eq!(high_score(30, 5807), 37305);
     }
 
+    #[test]
+    fn test_linked_list_high_score() {
+        assert_eq!(linked_list_high_score(9, 25), 32);
+        assert_eq!(linked_list_high_score(10, 1618), 8317);
+        assert_eq!(linked_list_high_score(30, 5807), 37305);
+    }
+
     // No test case for part 2 as it uses the exact same function high_score function
 
     #[cfg_attr(feature = "bench", bench)]
@@ -130,4 +163,26 @@ mod test {
         let input = ::std::fs::read_to_string("inputs/day-9.txt").expect("Unable to open file");
         b.iter(|| part2(&input).unwrap());
     }
+
+    #[cfg_attr(feature = "bench", bench)]
+    #[cfg(feature = "bench")]
+    fn bench_part1_linked_list(b: &mut Bencher) {
+        let input = ::std::fs::read_to_string("inputs/day-9.txt").expect("Unable to open file");
+        let Config {
+            players,
+            last_marble,
+        } = parse_line(input.trim()).unwrap();
+        b.iter(|| linked_list_high_score(players, last_marble));
+    }
+
+    #[cfg_attr(feature = "bench", bench)]
+    #[cfg(feature = "bench")]
+    fn bench_part2_linked_list(b: &mut Bencher) {
+        let input = ::std::fs::read_to_string("inputs/day-9.txt").expect("Unable to open file");
+        let Config {
+            players,
+            last_marble,
+        } = parse_line(input.trim()).unwrap();
+        b.iter(|| linked_list_high_score(players, last_marble * 100));
+    }
 }
@@ -0,0 +1,87 @@
+pub use grid::Grid;
+use grid::Order;
+
+// Dense 2D storage shared with the later years, which also use `grid::Grid`.
+//
+// Cells are indexed `grid[(x, y)]`, matching the 2023 solutions: the grid's "rows" run along x
+// and its "cols" along y. Cells are stored column-major so each line of the puzzle (fixed y) is
+// contiguous in memory, the same layout the puzzle input has.
+
+// Creates a `width`x`height` grid filled with the default value
+pub fn new<T: Default>(width: usize, height: usize) -> Grid<T> {
+    Grid::new_with_order(width, height, Order::ColumnMajor)
+}
+
+// Creates a `width`x`height` grid filled with copies of `value`
+pub fn init<T: Clone>(width: usize, height: usize, value: T) -> Grid<T> {
+    Grid::init_with_order(width, height, Order::ColumnMajor, value)
+}
+
+// (x, y) flavored accessors, so callers don't have to remember which grid axis is which
+pub trait GridExt<T> {
+    fn width(&self) -> usize;
+
+    fn height(&self) -> usize;
+
+    /// Iterates the cells of line `y`, from left to right
+    fn row(&self, y: usize) -> Box<dyn Iterator<Item = &T> + '_>;
+
+    /// Iterates the cells of column `x`, from top to bottom
+    fn column(&self, x: usize) -> Box<dyn Iterator<Item = &T> + '_>;
+
+    /// Draws the grid one line per row, using `glyph` to pick each cell's character
+    fn render<F: Fn(&T) -> char>(&self, glyph: F) -> String;
+}
+
+impl<T> GridExt<T> for Grid<T> {
+    fn width(&self) -> usize {
+        self.rows()
+    }
+
+    fn height(&self) -> usize {
+        self.cols()
+    }
+
+    fn row(&self, y: usize) -> Box<dyn Iterator<Item = &T> + '_> {
+        Box::new(self.iter_col(y))
+    }
+
+    fn column(&self, x: usize) -> Box<dyn Iterator<Item = &T> + '_> {
+        Box::new(self.iter_row(x))
+    }
+
+    fn render<F: Fn(&T) -> char>(&self, glyph: F) -> String {
+        let mut out = String::with_capacity((self.width() + 1) * self.height());
+        for y in 0..self.height() {
+            out.extend(self.row(y).map(&glyph));
+            out.push('\n');
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_xy_indexing() {
+        let mut grid = new::<u8>(3, 2);
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        grid[(2, 0)] = 1;
+        grid[(0, 1)] = 2;
+        assert_eq!(grid.get(2, 0), Some(&1));
+        assert_eq!(grid.get(0, 2), None);
+        // Lines are contiguous in memory
+        assert_eq!(grid.flatten(), &vec![0, 0, 1, 2, 0, 0]);
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let mut grid = init(3, 2, '.');
+        grid[(1, 1)] = '#';
+        assert_eq!(grid.row(1).collect::<String>(), ".#.");
+        assert_eq!(grid.column(1).collect::<String>(), ".#");
+        assert_eq!(grid.render(|c| *c), "...\n.#.\n");
+    }
+}
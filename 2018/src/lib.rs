@@ -14,6 +14,16 @@ use serde_json::Value;
 
 type Error = Box<dyn std::error::Error>;
 
+pub mod animate;
+pub mod grid;
+pub mod ocr;
+pub mod rect;
+pub mod ring;
+pub mod scheduler;
+pub mod svg;
+pub mod tree;
+pub mod voronoi;
+
 mod day1;
 mod day10;
 mod day11;
@@ -73,3 +83,20 @@ pub fn apply(input: Input) -> Result<Value, Error> {
         }
     }
 }
+
+// Draws a puzzle's geometry as an SVG, for the days that support it
+pub fn svg(input: Input) -> Result<svg::Svg, Error> {
+    match input.day {
+        6 => day6::svg(&input.input),
+        _ => Err(format!("Day {} does not support SVG output", input.day).into()),
+    }
+}
+
+// Animates a puzzle's simulation in the terminal, for the days that support it
+pub fn animate(input: Input, opts: &animate::Options) -> Result<(), Error> {
+    match input.day {
+        10 => day10::animate(&input.input, opts),
+        13 => day13::animate(&input.input, opts),
+        _ => Err(format!("Day {} does not support animation", input.day).into()),
+    }
+}
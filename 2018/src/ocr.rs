@@ -0,0 +1,99 @@
+use fxhash::FxHashMap;
+
+// Reads the block letters that some puzzles draw with '#' and '.' (e.g. 2018 day 10)
+//
+// Only the 10 pixel tall font those puzzles use is known. Glyphs are separated by columns that are
+// entirely blank, so letters may sit at any spacing.
+
+#[rustfmt::skip]
+const GLYPHS: &[(char, &str)] = &[
+    ('A', "..##.. .#..#. #....# #....# #....# ###### #....# #....# #....# #....#"),
+    ('B', "#####. #....# #....# #....# #####. #....# #....# #....# #....# #####."),
+    ('C', ".####. #....# #..... #..... #..... #..... #..... #..... #....# .####."),
+    ('E', "###### #..... #..... #..... #####. #..... #..... #..... #..... ######"),
+    ('F', "###### #..... #..... #..... #####. #..... #..... #..... #..... #....."),
+    ('G', ".####. #....# #..... #..... #..... #..### #....# #....# #...## .###.#"),
+    ('H', "#....# #....# #....# #....# ###### #....# #....# #....# #....# #....#"),
+    ('J', "...### ....#. ....#. ....#. ....#. ....#. ....#. #...#. #...#. .###.."),
+    ('K', "#....# #...#. #..#.. #.#... ##.... ##.... #.#... #..#.. #...#. #....#"),
+    ('L', "#..... #..... #..... #..... #..... #..... #..... #..... #..... ######"),
+    ('N', "#....# ##...# ##...# #.#..# #.#..# #..#.# #..#.# #...## #...## #....#"),
+    ('P', "#####. #....# #....# #....# #####. #..... #..... #..... #..... #....."),
+    ('R', "#####. #....# #....# #....# #####. #..#.. #...#. #...#. #....# #....#"),
+    ('X', "#....# #....# .#..#. .#..#. ..##.. ..##.. .#..#. .#..#. #....# #....#"),
+    ('Z', "###### .....# .....# ....#. ...#.. ..#... .#.... #..... #..... ######"),
+];
+
+lazy_static! {
+    // Glyph rows joined by spaces, mapped to their letter
+    static ref FONT: FxHashMap<&'static str, char> =
+        GLYPHS.iter().map(|(c, glyph)| (*glyph, *c)).collect();
+}
+
+// Decodes ASCII art made of '#' (lit) and anything else (unlit), one line per row.
+// Returns None if any glyph isn't a known letter.
+pub fn decode(art: &str) -> Option<String> {
+    let rows: Vec<&[u8]> = art
+        .lines()
+        .map(str::as_bytes)
+        .filter(|row| row.contains(&b'#'))
+        .collect();
+    let width = rows.iter().map(|row| row.len()).max()?;
+    let lit = |x: usize| rows.iter().any(|row| row.get(x) == Some(&b'#'));
+
+    let mut text = String::new();
+    let mut x = 0;
+    while x < width {
+        if !lit(x) {
+            x += 1;
+            continue;
+        }
+        let start = x;
+        while x < width && lit(x) {
+            x += 1;
+        }
+        let glyph: Vec<String> = rows
+            .iter()
+            .map(|row| {
+                (start..x)
+                    .map(|i| if row.get(i) == Some(&b'#') { '#' } else { '.' })
+                    .collect()
+            })
+            .collect();
+        text.push(*FONT.get(glyph.join(" ").as_str())?);
+    }
+    Some(text)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn art(glyphs: &[&str]) -> String {
+        let rows: Vec<Vec<&str>> = glyphs.iter().map(|g| g.split(' ').collect()).collect();
+        (0..rows[0].len())
+            .map(|y| {
+                let line: Vec<&str> = rows.iter().map(|g| g[y]).collect();
+                line.join("..") + "\n"
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_decode() {
+        for (c, glyph) in GLYPHS {
+            assert_eq!(decode(&art(&[glyph])), Some(c.to_string()));
+        }
+        let word: Vec<&str> = "ZAP"
+            .chars()
+            .map(|c| GLYPHS.iter().find(|g| g.0 == c).unwrap().1)
+            .collect();
+        assert_eq!(decode(&art(&word)), Some("ZAP".to_string()));
+    }
+
+    #[test]
+    fn test_unknown_glyph() {
+        assert_eq!(decode("#.#\n###\n#.#\n"), None);
+        assert_eq!(decode("....\n"), None);
+    }
+}
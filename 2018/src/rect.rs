@@ -0,0 +1,194 @@
+use std::cmp::{max, min};
+
+// An axis-aligned rectangle covering the half-open ranges x0..x1 and y0..y1
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub x0: i64,
+    pub y0: i64,
+    pub x1: i64,
+    pub y1: i64,
+}
+
+impl Rect {
+    /// Creates a `w`x`h` rectangle with its top-left corner at `(x, y)`
+    pub fn new(x: i64, y: i64, w: i64, h: i64) -> Rect {
+        Rect {
+            x0: x,
+            y0: y,
+            x1: x + w,
+            y1: y + h,
+        }
+    }
+
+    pub fn width(&self) -> i64 {
+        self.x1 - self.x0
+    }
+
+    pub fn height(&self) -> i64 {
+        self.y1 - self.y0
+    }
+
+    pub fn area(&self) -> u64 {
+        (self.width().max(0) * self.height().max(0)) as u64
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.x0 >= self.x1 || self.y0 >= self.y1
+    }
+
+    /// The region covered by both rectangles, or None if they don't share any area
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let rect = Rect {
+            x0: max(self.x0, other.x0),
+            y0: max(self.y0, other.y0),
+            x1: min(self.x1, other.x1),
+            y1: min(self.y1, other.y1),
+        };
+        if rect.is_empty() {
+            None
+        } else {
+            Some(rect)
+        }
+    }
+
+    pub fn overlaps(&self, other: &Rect) -> bool {
+        self.intersection(other).is_some()
+    }
+}
+
+// Total area covered by at least `min_count` of the rectangles.
+//
+// The plane is split along every rectangle edge (coordinate compression), so each compressed cell
+// is either fully inside or fully outside every rectangle. Coverage counts are accumulated with a 2D
+// difference array, making this O(n + X * Y) in the number of distinct edges rather than the area.
+pub fn covered_area(rects: &[Rect], min_count: usize) -> u64 {
+    let rects: Vec<&Rect> = rects.iter().filter(|r| !r.is_empty()).collect();
+    let xs = compress(rects.iter().flat_map(|r| vec![r.x0, r.x1]));
+    let ys = compress(rects.iter().flat_map(|r| vec![r.y0, r.y1]));
+    if xs.len() < 2 || ys.len() < 2 {
+        return 0;
+    }
+
+    let (nx, ny) = (xs.len(), ys.len());
+    let mut counts = vec![0i32; nx * ny];
+    for r in &rects {
+        let (x0, x1) = (index_of(&xs, r.x0), index_of(&xs, r.x1));
+        let (y0, y1) = (index_of(&ys, r.y0), index_of(&ys, r.y1));
+        counts[y0 * nx + x0] += 1;
+        counts[y0 * nx + x1] -= 1;
+        counts[y1 * nx + x0] -= 1;
+        counts[y1 * nx + x1] += 1;
+    }
+
+    let mut area = 0;
+    for j in 0..ny {
+        for i in 0..nx {
+            let mut count = counts[j * nx + i];
+            if i > 0 {
+                count += counts[j * nx + i - 1];
+            }
+            if j > 0 {
+                count += counts[(j - 1) * nx + i];
+            }
+            if i > 0 && j > 0 {
+                count -= counts[(j - 1) * nx + i - 1];
+            }
+            counts[j * nx + i] = count;
+
+            if count as usize >= min_count && i + 1 < nx && j + 1 < ny {
+                area += ((xs[i + 1] - xs[i]) * (ys[j + 1] - ys[j])) as u64;
+            }
+        }
+    }
+    area
+}
+
+// Total area covered by two or more of the rectangles
+pub fn overlap_area(rects: &[Rect]) -> u64 {
+    covered_area(rects, 2)
+}
+
+// Indices (in input order) of the rectangles that don't overlap any other rectangle.
+//
+// Sweeps left to right, only comparing each rectangle against the ones that start before it ends.
+pub fn non_overlapping(rects: &[Rect]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..rects.len()).collect();
+    order.sort_by_key(|&i| rects[i].x0);
+
+    let mut overlapped = vec![false; rects.len()];
+    for (n, &i) in order.iter().enumerate() {
+        for &j in &order[n + 1..] {
+            if rects[j].x0 >= rects[i].x1 {
+                break;
+            }
+            if rects[i].overlaps(&rects[j]) {
+                overlapped[i] = true;
+                overlapped[j] = true;
+            }
+        }
+    }
+
+    (0..rects.len()).filter(|&i| !overlapped[i]).collect()
+}
+
+fn compress<I: Iterator<Item = i64>>(coords: I) -> Vec<i64> {
+    let mut coords: Vec<i64> = coords.collect();
+    coords.sort_unstable();
+    coords.dedup();
+    coords
+}
+
+fn index_of(coords: &[i64], coord: i64) -> usize {
+    coords
+        .binary_search(&coord)
+        .expect("coordinate was not compressed")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_intersection() {
+        let a = Rect::new(1, 3, 4, 4);
+        let b = Rect::new(3, 1, 4, 4);
+        assert_eq!(a.intersection(&b), Some(Rect::new(3, 3, 2, 2)));
+        // Touching edges don't overlap
+        assert_eq!(a.intersection(&Rect::new(5, 3, 2, 2)), None);
+        assert_eq!(Rect::new(0, 0, 0, 5).area(), 0);
+    }
+
+    #[test]
+    fn test_covered_area() {
+        let rects = vec![
+            Rect::new(1, 3, 4, 4),
+            Rect::new(3, 1, 4, 4),
+            Rect::new(5, 5, 2, 2),
+        ];
+        assert_eq!(covered_area(&rects, 1), 16 + 16 + 4 - 4);
+        assert_eq!(overlap_area(&rects), 4);
+        assert_eq!(covered_area(&rects, 3), 0);
+        assert_eq!(covered_area(&[], 1), 0);
+    }
+
+    #[test]
+    fn test_arbitrary_coordinates() {
+        let rects = vec![
+            Rect::new(-5_000, -5_000, 10_000, 2),
+            Rect::new(4_000, -5_001, 2, 3),
+            Rect::new(1 << 40, 0, 1, 1),
+        ];
+        assert_eq!(overlap_area(&rects), 4);
+        assert_eq!(non_overlapping(&rects), vec![2]);
+    }
+
+    #[test]
+    fn test_non_overlapping() {
+        let rects = vec![
+            Rect::new(1, 3, 4, 4),
+            Rect::new(3, 1, 4, 4),
+            Rect::new(5, 5, 2, 2),
+        ];
+        assert_eq!(non_overlapping(&rects), vec![2]);
+    }
+}
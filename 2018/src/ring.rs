@@ -0,0 +1,122 @@
+use std::collections::VecDeque;
+
+// A circular buffer with a single cursor, backed by a VecDeque
+//
+// The cursor always sits just before the front of the deque, so the "next" element is the front.
+// Seeking is just a rotation of the deque, which is O(n) in the distance moved, but that's a cheap
+// memmove of contiguous elements rather than chasing pointers node-by-node like a linked list.
+#[derive(Debug, Clone, Default)]
+pub struct Ring<T> {
+    buf: VecDeque<T>,
+}
+
+impl<T> Ring<T> {
+    pub fn new() -> Ring<T> {
+        Ring {
+            buf: VecDeque::new(),
+        }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Ring<T> {
+        Ring {
+            buf: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    /// Inserts an element at the cursor. The new element becomes the next element.
+    pub fn insert(&mut self, elem: T) {
+        self.buf.push_front(elem)
+    }
+
+    /// Removes the next element without moving the cursor. Returns None if the ring is empty.
+    pub fn remove(&mut self) -> Option<T> {
+        self.buf.pop_front()
+    }
+
+    /// Gets the next element without moving the cursor.
+    pub fn peek(&self) -> Option<&T> {
+        self.buf.front()
+    }
+
+    /// Moves the cursor forward `n` elements (or backward for negative `n`), wrapping around the ring.
+    pub fn rotate(&mut self, n: isize) {
+        let len = self.buf.len();
+        if len == 0 {
+            return;
+        }
+        let steps = n.unsigned_abs() % len;
+        if n >= 0 {
+            self.buf.rotate_left(steps);
+        } else {
+            self.buf.rotate_right(steps);
+        }
+    }
+
+    pub fn seek_forward(&mut self, n: usize) {
+        self.rotate(n as isize)
+    }
+
+    pub fn seek_backward(&mut self, n: usize) {
+        self.rotate(-(n as isize))
+    }
+
+    /// Iterates the ring starting from the next element
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.buf.iter()
+    }
+}
+
+impl<T> From<Vec<T>> for Ring<T> {
+    fn from(vec: Vec<T>) -> Ring<T> {
+        Ring { buf: vec.into() }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn collect(ring: &Ring<u32>) -> Vec<u32> {
+        ring.iter().cloned().collect()
+    }
+
+    #[test]
+    fn test_insert_remove() {
+        let mut ring = Ring::new();
+        ring.insert(1);
+        ring.insert(2);
+        assert_eq!(collect(&ring), vec![2, 1]);
+        assert_eq!(ring.remove(), Some(2));
+        assert_eq!(ring.remove(), Some(1));
+        assert_eq!(ring.remove(), None);
+    }
+
+    #[test]
+    fn test_rotate_wraps() {
+        let mut ring = Ring::from(vec![0, 1, 2, 3]);
+        ring.seek_forward(1);
+        assert_eq!(collect(&ring), vec![1, 2, 3, 0]);
+        ring.seek_backward(2);
+        assert_eq!(collect(&ring), vec![3, 0, 1, 2]);
+        ring.rotate(9);
+        assert_eq!(collect(&ring), vec![0, 1, 2, 3]);
+        ring.rotate(-7);
+        assert_eq!(collect(&ring), vec![1, 2, 3, 0]);
+    }
+
+    #[test]
+    fn test_rotate_empty() {
+        let mut ring: Ring<u32> = Ring::new();
+        ring.rotate(3);
+        assert!(ring.is_empty());
+        assert_eq!(ring.peek(), None);
+    }
+}
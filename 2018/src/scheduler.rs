@@ -0,0 +1,197 @@
+use fxhash::FxHashMap;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::hash::Hash;
+use crate::Error;
+
+// Discrete-event simulation of workers processing a DAG of tasks.
+//
+// Whenever a worker is idle it picks up the smallest ready task (a task is ready once all of its
+// dependencies have finished). Rather than ticking a clock, time jumps straight to the next moment
+// a task finishes, so the cost depends on the number of tasks, not their durations.
+#[derive(Debug, Clone)]
+pub struct Scheduler<T> {
+    durations: FxHashMap<T, u32>,
+    dependents: FxHashMap<T, Vec<T>>,
+}
+
+// A task being worked on from `start` until `end` by a given worker
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Assignment<T> {
+    pub task: T,
+    pub worker: usize,
+    pub start: u32,
+    pub end: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule<T> {
+    // Assignments in the order they started
+    pub assignments: Vec<Assignment<T>>,
+    // Time at which the last task finishes
+    pub makespan: u32,
+}
+
+impl<T: Copy + Ord + Hash> Default for Scheduler<T> {
+    fn default() -> Self {
+        Scheduler::new()
+    }
+}
+
+impl<T: Copy + Ord + Hash> Scheduler<T> {
+    pub fn new() -> Scheduler<T> {
+        Scheduler {
+            durations: FxHashMap::default(),
+            dependents: FxHashMap::default(),
+        }
+    }
+
+    /// Adds a task (or updates its duration)
+    pub fn add_task(&mut self, task: T, duration: u32) {
+        self.durations.insert(task, duration);
+    }
+
+    /// Requires `before` to finish before `after` can start. Both tasks must already be added.
+    pub fn add_dependency(&mut self, before: T, after: T) {
+        self.dependents.entry(before).or_default().push(after);
+    }
+
+    /// Runs the simulation with `workers` workers. Fails if the dependencies contain a cycle or
+    /// refer to a task that was never added.
+    pub fn run(&self, workers: usize) -> Result<Schedule<T>, Error> {
+        if workers == 0 {
+            return Err("Cannot schedule tasks without any workers".into());
+        }
+
+        let mut waiting_on: FxHashMap<T, usize> = self.durations.keys().map(|t| (*t, 0)).collect();
+        for (before, afters) in &self.dependents {
+            if !self.durations.contains_key(before) {
+                return Err("Dependency on an unknown task".into());
+            }
+            for after in afters {
+                *waiting_on
+                    .get_mut(after)
+                    .ok_or("Dependency on an unknown task")? += 1;
+            }
+        }
+
+        let mut ready: BinaryHeap<Reverse<T>> = waiting_on
+            .iter()
+            .filter(|(_, count)| **count == 0)
+            .map(|(task, _)| Reverse(*task))
+            .collect();
+        let mut idle: BinaryHeap<Reverse<usize>> = (0..workers).map(Reverse).collect();
+        // Ordered by end time, so the next event is always on top
+        let mut running: BinaryHeap<Reverse<(u32, usize, T)>> = BinaryHeap::new();
+
+        let mut now = 0;
+        let mut assignments = Vec::with_capacity(self.durations.len());
+        loop {
+            while !ready.is_empty() && !idle.is_empty() {
+                let Reverse(task) = ready.pop().unwrap();
+                let Reverse(worker) = idle.pop().unwrap();
+                let end = now + self.durations[&task];
+                assignments.push(Assignment {
+                    task,
+                    worker,
+                    start: now,
+                    end,
+                });
+                running.push(Reverse((end, worker, task)));
+            }
+
+            let next = match running.peek() {
+                Some(Reverse((end, _, _))) => *end,
+                None => break,
+            };
+            now = next;
+
+            // Finish everything ending now before handing out work, so freed tasks compete fairly
+            while let Some(Reverse((end, worker, task))) = running.peek().cloned() {
+                if end != now {
+                    break;
+                }
+                running.pop();
+                idle.push(Reverse(worker));
+                for dependent in self.dependents.get(&task).into_iter().flatten() {
+                    let count = waiting_on.get_mut(dependent).unwrap();
+                    *count -= 1;
+                    if *count == 0 {
+                        ready.push(Reverse(*dependent));
+                    }
+                }
+            }
+        }
+
+        if assignments.len() < self.durations.len() {
+            return Err(
+                "Tasks could not be scheduled because their dependencies form a cycle".into(),
+            );
+        }
+        Ok(Schedule {
+            assignments,
+            makespan: now,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn diamond() -> Scheduler<char> {
+        // a -> b -> d, a -> c -> d
+        let mut scheduler = Scheduler::new();
+        for &(task, duration) in &[('a', 1), ('b', 5), ('c', 2), ('d', 1)] {
+            scheduler.add_task(task, duration);
+        }
+        scheduler.add_dependency('a', 'b');
+        scheduler.add_dependency('a', 'c');
+        scheduler.add_dependency('b', 'd');
+        scheduler.add_dependency('c', 'd');
+        scheduler
+    }
+
+    #[test]
+    fn test_parallel() {
+        let schedule = diamond().run(2).unwrap();
+        assert_eq!(schedule.makespan, 7);
+        let timeline: Vec<_> = schedule
+            .assignments
+            .iter()
+            .map(|a| (a.task, a.worker, a.start, a.end))
+            .collect();
+        assert_eq!(
+            timeline,
+            vec![
+                ('a', 0, 0, 1),
+                ('b', 0, 1, 6),
+                ('c', 1, 1, 3),
+                ('d', 0, 6, 7)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_single_worker() {
+        let schedule = diamond().run(1).unwrap();
+        assert_eq!(schedule.makespan, 9);
+        let order: String = schedule.assignments.iter().map(|a| a.task).collect();
+        assert_eq!(order, "abcd");
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut scheduler = diamond();
+        scheduler.add_dependency('d', 'a');
+        assert!(scheduler.run(2).is_err());
+        assert!(diamond().run(0).is_err());
+
+        let mut scheduler = diamond();
+        scheduler.add_dependency('z', 'a');
+        assert!(scheduler.run(2).is_err());
+        let mut scheduler = diamond();
+        scheduler.add_dependency('a', 'z');
+        assert!(scheduler.run(2).is_err());
+    }
+}
@@ -0,0 +1,158 @@
+// Minimal SVG writer for drawing puzzle geometry
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// An SVG document built up from simple shapes, drawn in the order they're added
+#[derive(Debug, Clone)]
+pub struct Svg {
+    width: f64,
+    height: f64,
+    elements: Vec<String>,
+}
+
+impl Svg {
+    /// Creates an empty drawing with a `width`x`height` view box
+    pub fn new(width: f64, height: f64) -> Svg {
+        Svg {
+            width,
+            height,
+            elements: Vec::new(),
+        }
+    }
+
+    /// Draws a filled circle centered at `(x, y)`
+    pub fn point(&mut self, x: f64, y: f64, radius: f64, fill: &str) -> &mut Svg {
+        self.elements.push(format!(
+            r#"<circle cx="{}" cy="{}" r="{}" fill="{}"/>"#,
+            x,
+            y,
+            radius,
+            escape(fill)
+        ));
+        self
+    }
+
+    /// Draws a line segment between two points
+    pub fn line(&mut self, from: (f64, f64), to: (f64, f64), stroke: &str, width: f64) -> &mut Svg {
+        self.elements.push(format!(
+            r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="{}" stroke-width="{}"/>"#,
+            from.0,
+            from.1,
+            to.0,
+            to.1,
+            escape(stroke),
+            width
+        ));
+        self
+    }
+
+    /// Draws a closed polygon through the given vertices
+    pub fn polygon(&mut self, vertices: &[(f64, f64)], fill: &str, stroke: &str) -> &mut Svg {
+        let points: Vec<String> = vertices
+            .iter()
+            .map(|(x, y)| format!("{},{}", x, y))
+            .collect();
+        self.elements.push(format!(
+            r#"<polygon points="{}" fill="{}" stroke="{}"/>"#,
+            points.join(" "),
+            escape(fill),
+            escape(stroke)
+        ));
+        self
+    }
+
+    /// Draws an axis-aligned rectangle with its top-left corner at `(x, y)`
+    pub fn rect(&mut self, x: f64, y: f64, width: f64, height: f64, fill: &str) -> &mut Svg {
+        self.elements.push(format!(
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+            x,
+            y,
+            width,
+            height,
+            escape(fill)
+        ));
+        self
+    }
+
+    /// Draws text centered at `(x, y)`
+    pub fn text(&mut self, x: f64, y: f64, size: f64, label: &str) -> &mut Svg {
+        self.elements.push(format!(
+            r#"<text x="{}" y="{}" font-size="{}" text-anchor="middle" dominant-baseline="central">{}</text>"#,
+            x,
+            y,
+            size,
+            escape(label)
+        ));
+        self
+    }
+
+    /// Draws a `size`x`size` square for the grid cell at `(x, y)`, with an optional centered label
+    pub fn cell(
+        &mut self,
+        x: usize,
+        y: usize,
+        size: f64,
+        fill: &str,
+        label: Option<&str>,
+    ) -> &mut Svg {
+        let (left, top) = (x as f64 * size, y as f64 * size);
+        self.rect(left, top, size, size, fill);
+        if let Some(label) = label {
+            self.text(left + size / 2.0, top + size / 2.0, size * 0.6, label);
+        }
+        self
+    }
+
+    /// Writes the document to a file
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+}
+
+impl fmt::Display for Svg {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {} {}" width="{}" height="{}">"#,
+            self.width, self.height, self.width, self.height
+        )?;
+        for element in &self.elements {
+            writeln!(f, "  {}", element)?;
+        }
+        writeln!(f, "</svg>")
+    }
+}
+
+// Escapes the characters that aren't allowed in XML text or attribute values
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_shapes() {
+        let mut svg = Svg::new(20.0, 10.0);
+        svg.point(1.0, 2.0, 0.5, "red")
+            .line((0.0, 0.0), (20.0, 10.0), "black", 1.0)
+            .polygon(&[(0.0, 0.0), (4.0, 0.0), (4.0, 3.0)], "none", "blue")
+            .cell(1, 0, 10.0, "#eee", Some("A&B"));
+        let doc = svg.to_string();
+        assert!(doc.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 20 10""#));
+        assert!(doc.contains(r#"<circle cx="1" cy="2" r="0.5" fill="red"/>"#));
+        assert!(doc
+            .contains(r#"<line x1="0" y1="0" x2="20" y2="10" stroke="black" stroke-width="1"/>"#));
+        assert!(doc.contains(r#"<polygon points="0,0 4,0 4,3" fill="none" stroke="blue"/>"#));
+        assert!(doc.contains(r##"<rect x="10" y="0" width="10" height="10" fill="#eee"/>"##));
+        assert!(doc.contains(">A&amp;B</text>"));
+        assert!(doc.trim_end().ends_with("</svg>"));
+    }
+}
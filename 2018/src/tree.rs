@@ -0,0 +1,139 @@
+// An arena-backed tree, where nodes refer to their children by index into a single Vec.
+//
+// Traversals use an explicit stack rather than recursion, so arbitrarily deep trees are fine.
+
+pub type NodeId = usize;
+
+#[derive(Debug, Clone)]
+pub struct Node<T> {
+    pub data: T,
+    pub children: Vec<NodeId>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Tree<T> {
+    nodes: Vec<Node<T>>,
+}
+
+impl<T> Tree<T> {
+    /// Creates a tree containing only a root node
+    pub fn new(root: T) -> Tree<T> {
+        Tree {
+            nodes: vec![Node {
+                data: root,
+                children: Vec::new(),
+            }],
+        }
+    }
+
+    pub fn root(&self) -> NodeId {
+        0
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn node(&self, id: NodeId) -> &Node<T> {
+        &self.nodes[id]
+    }
+
+    pub fn data_mut(&mut self, id: NodeId) -> &mut T {
+        &mut self.nodes[id].data
+    }
+
+    /// Appends a new last child to `parent`, returning its id
+    pub fn add_child(&mut self, parent: NodeId, data: T) -> NodeId {
+        let id = self.nodes.len();
+        self.nodes.push(Node {
+            data,
+            children: Vec::new(),
+        });
+        self.nodes[parent].children.push(id);
+        id
+    }
+
+    /// Visits every node parents-first, with children in order, along with its depth below the root
+    pub fn visit<F: FnMut(NodeId, &T, usize)>(&self, mut f: F) {
+        let mut stack = vec![(self.root(), 0)];
+        while let Some((id, depth)) = stack.pop() {
+            let node = &self.nodes[id];
+            f(id, &node.data, depth);
+            stack.extend(node.children.iter().rev().map(|child| (*child, depth + 1)));
+        }
+    }
+
+    /// Combines the tree bottom-up: `f` is given each node's data and the results of its children
+    /// (in order), and the result for the root is returned
+    pub fn fold<R, F: FnMut(&T, &[R]) -> R>(&self, mut f: F) -> R {
+        let mut order = Vec::with_capacity(self.nodes.len());
+        self.visit(|id, _, _| order.push(id));
+
+        // Parents come before their children in `order`, so walking it backwards means every
+        // child's result is ready by the time its parent needs it
+        let mut results: Vec<Option<R>> = (0..self.nodes.len()).map(|_| None).collect();
+        let mut child_results = Vec::new();
+        for &id in order.iter().rev() {
+            let node = &self.nodes[id];
+            child_results.clear();
+            child_results.extend(
+                node.children
+                    .iter()
+                    .map(|child| results[*child].take().expect("child folded twice")),
+            );
+            results[id] = Some(f(&node.data, &child_results));
+        }
+        results[self.root()].take().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sample() -> Tree<char> {
+        // a
+        // ├── b
+        // │   └── d
+        // └── c
+        let mut tree = Tree::new('a');
+        let b = tree.add_child(0, 'b');
+        tree.add_child(0, 'c');
+        tree.add_child(b, 'd');
+        tree
+    }
+
+    #[test]
+    fn test_visit() {
+        let mut seen = Vec::new();
+        sample().visit(|_, data, depth| seen.push((*data, depth)));
+        assert_eq!(seen, vec![('a', 0), ('b', 1), ('d', 2), ('c', 1)]);
+    }
+
+    #[test]
+    fn test_fold() {
+        let tree = sample();
+        let postorder =
+            tree.fold(|data, children: &[String]| children.concat() + &data.to_string());
+        assert_eq!(postorder, "dbca");
+        assert_eq!(
+            tree.fold(|_, children: &[usize]| 1 + children.iter().sum::<usize>()),
+            4
+        );
+    }
+
+    #[test]
+    fn test_deep_tree() {
+        let mut tree = Tree::new(0);
+        let mut parent = tree.root();
+        for depth in 1..1_000_000 {
+            parent = tree.add_child(parent, depth);
+        }
+        let height = tree.fold(|_, children: &[usize]| children.iter().max().map_or(0, |h| h + 1));
+        assert_eq!(height, 999_999);
+    }
+}
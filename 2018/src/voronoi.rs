@@ -0,0 +1,234 @@
+use std::collections::VecDeque;
+use crate::grid::{self, Grid, GridExt};
+
+// A Manhattan-distance Voronoi diagram over the bounding box of a set of sites
+//
+// Every cell in the box is labeled with the site closest to it, or as a tie when two or more sites
+// are equally close. Labels are filled in by a breadth-first search started from all sites at once,
+// which visits each cell once instead of measuring it against every site.
+#[derive(Debug, Clone)]
+pub struct Voronoi {
+    // Coordinate of the top-left cell of the bounding box
+    origin: (i64, i64),
+    owners: Grid<Owner>,
+    sites: usize,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Owner {
+    #[default]
+    Unreached,
+    Site(usize),
+    Tie,
+}
+
+impl Owner {
+    fn merge(self, other: Owner) -> Owner {
+        match (self, other) {
+            (Owner::Unreached, owner) | (owner, Owner::Unreached) => owner,
+            (Owner::Site(a), Owner::Site(b)) if a == b => self,
+            _ => Owner::Tie,
+        }
+    }
+}
+
+impl Voronoi {
+    pub fn new(sites: &[(i64, i64)]) -> Voronoi {
+        let (origin, (x1, y1)) = match bounding_box(sites) {
+            Some(bounds) => bounds,
+            None => {
+                return Voronoi {
+                    origin: (0, 0),
+                    owners: grid::new(0, 0),
+                    sites: 0,
+                }
+            }
+        };
+        let width = (x1 - origin.0 + 1) as usize;
+        let height = (y1 - origin.1 + 1) as usize;
+        let mut owners: Grid<Owner> = grid::new(width, height);
+        let mut distances = grid::init(width, height, usize::MAX);
+
+        let mut queue = VecDeque::new();
+        for (i, &(x, y)) in sites.iter().enumerate() {
+            let cell = ((x - origin.0) as usize, (y - origin.1) as usize);
+            owners[cell] = owners[cell].merge(Owner::Site(i));
+            distances[cell] = 0;
+            queue.push_back(cell);
+        }
+
+        // The box is convex, so the grid distance found by the search is the Manhattan distance.
+        // A cell is tied if any of its neighbors one step closer to the sites lead to different sites.
+        while let Some((x, y)) = queue.pop_front() {
+            let next = distances[(x, y)] + 1;
+            let neighbors = [
+                (x.wrapping_sub(1), y),
+                (x + 1, y),
+                (x, y.wrapping_sub(1)),
+                (x, y + 1),
+            ];
+            for &cell in &neighbors {
+                if cell.0 >= width || cell.1 >= height {
+                    continue;
+                }
+                if distances[cell] == usize::MAX {
+                    distances[cell] = next;
+                    queue.push_back(cell);
+                }
+                if distances[cell] == next {
+                    owners[cell] = owners[cell].merge(owners[(x, y)]);
+                }
+            }
+        }
+
+        Voronoi {
+            origin,
+            owners,
+            sites: sites.len(),
+        }
+    }
+
+    /// Top-left corner of the bounding box
+    pub fn origin(&self) -> (i64, i64) {
+        self.origin
+    }
+
+    /// Labels for every cell of the bounding box, indexed relative to `origin`
+    pub fn owners(&self) -> &Grid<Owner> {
+        &self.owners
+    }
+
+    /// Index of the single closest site, or None for ties and coordinates outside the bounding box
+    pub fn owner(&self, x: i64, y: i64) -> Option<usize> {
+        let (dx, dy) = (x - self.origin.0, y - self.origin.1);
+        if dx < 0 || dy < 0 {
+            return None;
+        }
+        match self.owners.get(dx as usize, dy as usize) {
+            Some(Owner::Site(i)) => Some(*i),
+            _ => None,
+        }
+    }
+
+    /// Whether each site's region extends forever
+    ///
+    /// Stepping outward from the bounding box moves a cell one unit further from every site, so the
+    /// closest site never changes. Any region that reaches the edge of the box is therefore infinite.
+    pub fn infinite(&self) -> Vec<bool> {
+        let mut infinite = vec![false; self.sites];
+        let (width, height) = (self.owners.width(), self.owners.height());
+        for ((x, y), owner) in self.owners.indexed_iter() {
+            if let Owner::Site(i) = owner {
+                if x == 0 || y == 0 || x + 1 == width || y + 1 == height {
+                    infinite[*i] = true;
+                }
+            }
+        }
+        infinite
+    }
+
+    /// Number of cells in each site's region, or None if the region is infinite
+    pub fn areas(&self) -> Vec<Option<usize>> {
+        let mut areas = vec![0; self.sites];
+        for owner in self.owners.iter() {
+            if let Owner::Site(i) = owner {
+                areas[*i] += 1;
+            }
+        }
+        self.infinite()
+            .into_iter()
+            .zip(areas)
+            .map(|(infinite, area)| if infinite { None } else { Some(area) })
+            .collect()
+    }
+}
+
+fn bounding_box(sites: &[(i64, i64)]) -> Option<((i64, i64), (i64, i64))> {
+    let &first = sites.first()?;
+    Some(sites.iter().fold((first, first), |(lo, hi), &(x, y)| {
+        ((lo.0.min(x), lo.1.min(y)), (hi.0.max(x), hi.1.max(y)))
+    }))
+}
+
+// Number of cells whose total Manhattan distance to all sites is less than `bound`.
+//
+// The total splits into independent x and y sums, so each axis is summed once per coordinate and
+// pairs are counted with a binary search, rather than measuring every cell against every site. The
+// region can extend past the bounding box, and the search range accounts for that.
+pub fn region_within(sites: &[(i64, i64)], bound: u64) -> usize {
+    let (lo, hi) = match bounding_box(sites) {
+        Some(bounds) => bounds,
+        None => return 0,
+    };
+    // Outside the box every step away adds one unit per site to the total
+    let margin = (bound / sites.len() as u64) as i64 + 1;
+
+    let xs: Vec<i64> = sites.iter().map(|site| site.0).collect();
+    let ys: Vec<i64> = sites.iter().map(|site| site.1).collect();
+    let x_sums = distance_sums(xs, lo.0 - margin, hi.0 + margin);
+    let mut y_sums = distance_sums(ys, lo.1 - margin, hi.1 + margin);
+    y_sums.sort_unstable();
+
+    x_sums
+        .iter()
+        .map(|x_sum| y_sums.partition_point(|y_sum| x_sum + y_sum < bound))
+        .sum()
+}
+
+// Total distance from each coordinate in lo..=hi to every coordinate in `coords`
+fn distance_sums(mut coords: Vec<i64>, lo: i64, hi: i64) -> Vec<u64> {
+    coords.sort_unstable();
+    let n = coords.len() as i64;
+    let mut sum: i64 = coords.iter().map(|c| (c - lo).abs()).sum();
+    let mut below = 0;
+
+    let mut sums = Vec::with_capacity((hi - lo + 1) as usize);
+    for x in lo..=hi {
+        sums.push(sum as u64);
+        // Moving to x + 1 gets closer to everything after x and further from everything else
+        while (below as usize) < coords.len() && coords[below as usize] <= x {
+            below += 1;
+        }
+        sum += below - (n - below);
+    }
+    sums
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SITES: [(i64, i64); 6] = [(1, 1), (1, 6), (8, 3), (3, 4), (5, 5), (8, 9)];
+
+    #[test]
+    fn test_owners() {
+        let voronoi = Voronoi::new(&SITES);
+        assert_eq!(voronoi.origin(), (1, 1));
+        assert_eq!(voronoi.owner(1, 1), Some(0));
+        assert_eq!(voronoi.owner(5, 2), Some(4));
+        // Equally close to (1, 1) and (5, 5)
+        assert_eq!(voronoi.owner(5, 1), None);
+        assert_eq!(voronoi.owner(0, 0), None);
+    }
+
+    #[test]
+    fn test_areas() {
+        let voronoi = Voronoi::new(&SITES);
+        assert_eq!(
+            voronoi.infinite(),
+            vec![true, true, true, false, false, true]
+        );
+        assert_eq!(
+            voronoi.areas(),
+            vec![None, None, None, Some(9), Some(17), None]
+        );
+    }
+
+    #[test]
+    fn test_region_within() {
+        assert_eq!(region_within(&SITES, 32), 16);
+        // The region around a lone site is a diamond reaching past the one-cell bounding box
+        assert_eq!(region_within(&[(0, 0)], 3), 13);
+        assert_eq!(region_within(&[], 10), 0);
+    }
+}
@@ -1,12 +1,56 @@
 use anyhow::{bail, format_err, Context, Result};
 use std::fs;
+use std::time::Duration;
 
 fn main() -> Result<()> {
     let mut args = ::std::env::args().skip(1);
     if args.len() < 1 {
-        bail!("USAGE: aoc DAY[-PART] [FILE]");
+        bail!(
+            "USAGE: aoc DAY[-PART] [FILE] [--render OUT.gif|OUT.png [--delay MS]] [--svg OUT.svg] [--expansion N]"
+        );
     }
 
+    // Separate flags from the positional puzzle and file arguments
+    let mut render_path = None;
+    let mut svg_path = None;
+    let mut expansion = None;
+    let mut delay = Duration::from_millis(100);
+    let mut positional = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--render" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| format_err!("--render requires an output file"))?;
+                render_path = Some(path);
+            }
+            "--svg" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| format_err!("--svg requires an output file"))?;
+                svg_path = Some(path);
+            }
+            "--expansion" => {
+                let factor = args
+                    .next()
+                    .ok_or_else(|| format_err!("--expansion requires a factor"))?
+                    .parse()
+                    .context("Failed to parse --expansion factor")?;
+                expansion = Some(factor);
+            }
+            "--delay" => {
+                let ms = args
+                    .next()
+                    .ok_or_else(|| format_err!("--delay requires a number of milliseconds"))?
+                    .parse()
+                    .context("Failed to parse --delay milliseconds")?;
+                delay = Duration::from_millis(ms);
+            }
+            _ => positional.push(arg),
+        }
+    }
+    let mut args = positional.into_iter();
+
     let puzzle = args
         .next()
         .ok_or_else(|| format_err!("Must specifiy a puzzle (e.g. 2-1 for day 2 part 1)"))?;
@@ -25,7 +69,21 @@ fn main() -> Result<()> {
 
     let file_path = args.next().unwrap_or_else(|| format!("inputs/day{}", day));
     let input = fs::read_to_string(file_path).context("Unable to open input file")?;
-    let output = aoc::apply(aoc::Input { day, part, input })?;
+    let input = aoc::Input { day, part, input };
+
+    if let Some(path) = render_path {
+        let frames = aoc::render(input)?;
+        return aoc::render::save(path, &frames, delay);
+    }
+    if let Some(path) = svg_path {
+        let svg = aoc::svg(input, expansion)?;
+        return svg.save(path).context("Unable to write SVG file");
+    }
+
+    let output = match expansion {
+        Some(factor) => aoc::expansion(input, factor)?,
+        None => aoc::apply(input)?,
+    };
 
     println!("{}", output);
     Ok(())
@@ -0,0 +1,177 @@
+//! Multisets, storing how many of each item there are
+//!
+//! Counts form a lattice under the subset order: [`Counts::join`] is the smallest multiset
+//! containing both sides and [`Counts::meet`] the largest contained in both.
+
+use anyhow::{Context, Error, Result};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+/// A multiset of `K`. Keys that were never added have a count of zero.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Counts<K: Ord>(BTreeMap<K, u32>);
+
+impl<K: Ord> Default for Counts<K> {
+    fn default() -> Self {
+        Counts(BTreeMap::new())
+    }
+}
+
+impl<K: Ord + Clone> Counts<K> {
+    pub fn new() -> Counts<K> {
+        Counts::default()
+    }
+
+    /// How many of `key` there are
+    pub fn get(&self, key: &K) -> u32 {
+        self.0.get(key).copied().unwrap_or(0)
+    }
+
+    /// Adds `count` more of `key`
+    pub fn add(&mut self, key: K, count: u32) {
+        if count > 0 {
+            *self.0.entry(key).or_default() += count;
+        }
+    }
+
+    /// Keys with a nonzero count, in order, with their counts
+    pub fn iter(&self) -> impl Iterator<Item = (&K, u32)> {
+        self.0.iter().map(|(k, n)| (k, *n))
+    }
+
+    /// Total number of items, counting repeats
+    pub fn total(&self) -> u32 {
+        self.0.values().sum()
+    }
+
+    /// Largest count of each key from either side
+    pub fn join(&self, other: &Counts<K>) -> Counts<K> {
+        let mut joined = self.clone();
+        for (key, n) in other.iter() {
+            let count = joined.0.entry(key.clone()).or_default();
+            *count = (*count).max(n);
+        }
+        joined
+    }
+
+    /// Smallest count of each key from either side
+    pub fn meet(&self, other: &Counts<K>) -> Counts<K> {
+        self.iter()
+            .map(|(key, n)| (key.clone(), n.min(other.get(key))))
+            .collect()
+    }
+
+    /// Whether every key is counted at most as many times as in `other`
+    pub fn is_subset(&self, other: &Counts<K>) -> bool {
+        self.iter().all(|(key, n)| n <= other.get(key))
+    }
+
+    /// Product of the counts of `keys`, where missing keys count as zero
+    pub fn product<'a>(&self, keys: impl IntoIterator<Item = &'a K>) -> u64
+    where
+        K: 'a,
+    {
+        keys.into_iter().map(|key| self.get(key) as u64).product()
+    }
+}
+
+impl<K: Ord + Clone> FromIterator<(K, u32)> for Counts<K> {
+    fn from_iter<I: IntoIterator<Item = (K, u32)>>(iter: I) -> Self {
+        let mut counts = Counts::new();
+        for (key, n) in iter {
+            counts.add(key, n);
+        }
+        counts
+    }
+}
+
+/// Subset order: Less is a strict subset, Greater a strict superset, and None if neither
+/// contains the other
+impl<K: Ord + Clone> PartialOrd for Counts<K> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self.is_subset(other), other.is_subset(self)) {
+            (true, true) => Some(Ordering::Equal),
+            (true, false) => Some(Ordering::Less),
+            (false, true) => Some(Ordering::Greater),
+            (false, false) => None,
+        }
+    }
+}
+
+/// Parses a list like "3 blue, 4 red". A key listed more than once has its counts added.
+impl<K> FromStr for Counts<K>
+where
+    K: Ord + Clone + FromStr,
+    <K as FromStr>::Err: std::error::Error + Send + Sync + 'static,
+{
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        s.split(',')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .map(|part| {
+                let (count, key) = part
+                    .split_once(' ')
+                    .with_context(|| format!("Expected count & item: {part}"))?;
+                let count = count.parse::<u32>()?;
+                let key = key
+                    .trim()
+                    .parse()
+                    .with_context(|| format!("Unknown item: {key}"))?;
+                Ok((key, count))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn counts(s: &str) -> Counts<String> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let c = counts("3 blue, 4 red, 1 blue");
+        assert_eq!(c.get(&"blue".to_string()), 4);
+        assert_eq!(c.get(&"red".to_string()), 4);
+        assert_eq!(c.get(&"green".to_string()), 0);
+        assert_eq!(c.total(), 8);
+        assert_eq!(counts(""), Counts::new());
+        assert!("3".parse::<Counts<String>>().is_err());
+        assert!("x red".parse::<Counts<String>>().is_err());
+    }
+
+    #[test]
+    fn test_lattice() {
+        let a = counts("3 blue, 4 red");
+        let b = counts("1 blue, 6 red, 2 green");
+        assert_eq!(a.join(&b), counts("3 blue, 6 red, 2 green"));
+        assert_eq!(a.meet(&b), counts("1 blue, 4 red"));
+        assert_eq!(a.join(&Counts::new()), a);
+        assert_eq!(a.meet(&Counts::new()), Counts::new());
+    }
+
+    #[test]
+    fn test_subset_order() {
+        let a = counts("3 blue, 4 red");
+        assert!(a.is_subset(&a));
+        assert!(a < counts("3 blue, 5 red"));
+        assert!(a < counts("3 blue, 4 red, 1 green"));
+        assert!(a > counts("4 red"));
+        assert_eq!(a.partial_cmp(&counts("4 blue, 3 red")), None);
+        assert!(a >= a.clone());
+    }
+
+    #[test]
+    fn test_product() {
+        let a = counts("3 blue, 4 red");
+        let keys = ["blue".to_string(), "red".to_string()];
+        assert_eq!(a.product(&keys), 12);
+        assert_eq!(a.product(&["green".to_string()]), 0);
+    }
+}
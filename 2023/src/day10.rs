@@ -1,19 +1,54 @@
 //! [Advent of Code Day 10](https://adventofcode.com/2023/day/10)
 
+use crate::geometry;
 use crate::prelude::*;
+use crate::render::{self, Image};
 use grid::Grid;
+use std::collections::HashSet;
 
 pub fn part1(input: &str) -> Result<usize> {
     let field = Field::from_str(input)?;
-    let cycle = field.find_loop_path();
-    Ok((cycle.len() + 1) / 2)
+    let cycle = field.find_loop_path()?;
+    Ok(cycle.len().div_ceil(2))
 }
 
-pub fn part2(input: &str) -> Result<u32> {
-    let mut field = Field::from_str(input)?;
-    field.remove_unused_pipes();
-    let count = field.count_enclosed_ground();
-    Ok(count)
+/// Counts the tiles enclosed by the loop
+///
+/// Each tile on the loop is a vertex of a lattice polygon, and the loop itself is the boundary,
+/// so Pick's theorem gives the enclosed tiles straight from the polygon's area.
+pub fn part2(input: &str) -> Result<u64> {
+    let field = Field::from_str(input)?;
+    let polygon = field
+        .find_loop_path()?
+        .iter()
+        .map(|c| (c.x as i64, c.y as i64))
+        .collect_vec();
+    Ok(geometry::interior_points(&polygon))
+}
+
+/// Renders the loop (blue) from the start tile (red), with enclosed tiles (green),
+/// discarded pipes (gray) and outside ground (black)
+pub fn render(input: &str) -> Result<Vec<Image>> {
+    let field = Field::from_str(input)?;
+    let start = field.find_start()?;
+    let mut cleaned = field.clone();
+    cleaned.remove_unused_pipes()?;
+    let enclosed: HashSet<(usize, usize)> = cleaned
+        .enclosed_ground()
+        .into_iter()
+        .map(|c| (c.x, c.y))
+        .collect();
+
+    let image = Image::from_fn(field.width(), field.height(), 4, |x, y| {
+        match (cleaned.tile((x, y)), field.tile((x, y))) {
+            _ if (x, y) == (start.x, start.y) => render::RED,
+            _ if enclosed.contains(&(x, y)) => render::GREEN,
+            (Tile::Ground, Tile::Ground) => render::BLACK,
+            (Tile::Ground, _) => render::GRAY,
+            _ => render::BLUE,
+        }
+    });
+    Ok(vec![image])
 }
 
 #[derive(Debug, Clone, Deref)]
@@ -51,6 +86,72 @@ impl TryFrom<char> for Tile {
     }
 }
 
+/// A set of tile sides, one bit per side
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Sides(u8);
+
+impl Sides {
+    const NONE: Sides = Sides(0);
+    const N: Sides = Sides(1);
+    const E: Sides = Sides(2);
+    const S: Sides = Sides(4);
+    const W: Sides = Sides(8);
+    const EACH: [Sides; 4] = [Sides::N, Sides::E, Sides::S, Sides::W];
+
+    fn contains(self, side: Sides) -> bool {
+        self.0 & side.0 == side.0
+    }
+
+    fn len(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    /// Each side in the set
+    fn iter(self) -> impl Iterator<Item = Sides> {
+        Sides::EACH.into_iter().filter(move |side| self.contains(*side))
+    }
+
+    /// The facing side: N <-> S and E <-> W
+    fn opposite(self) -> Sides {
+        Sides(((self.0 << 2) | (self.0 >> 2)) & 0b1111)
+    }
+}
+
+impl std::ops::BitOr for Sides {
+    type Output = Sides;
+    fn bitor(self, rhs: Sides) -> Sides {
+        Sides(self.0 | rhs.0)
+    }
+}
+
+impl Tile {
+    const PIPES: [Tile; 6] = [
+        Tile::PipeNS,
+        Tile::PipeWE,
+        Tile::PipeNE,
+        Tile::PipeNW,
+        Tile::PipeSW,
+        Tile::PipeSE,
+    ];
+
+    /// The sides a pipe opens onto. The start tile hides its pipe, so it has none of its own.
+    fn sides(self) -> Sides {
+        match self {
+            Tile::PipeNS => Sides::N | Sides::S,
+            Tile::PipeWE => Sides::W | Sides::E,
+            Tile::PipeNE => Sides::N | Sides::E,
+            Tile::PipeNW => Sides::N | Sides::W,
+            Tile::PipeSW => Sides::S | Sides::W,
+            Tile::PipeSE => Sides::S | Sides::E,
+            Tile::Ground | Tile::Start => Sides::NONE,
+        }
+    }
+
+    fn from_sides(sides: Sides) -> Option<Tile> {
+        Tile::PIPES.into_iter().find(|t| t.sides() == sides)
+    }
+}
+
 #[derive(Debug, Clone, Copy, Display, PartialEq)]
 #[display("({x},{y})")]
 struct Coord {
@@ -65,39 +166,45 @@ impl From<(usize, usize)> for Coord {
 }
 
 impl Coord {
-    fn offset(&self, dx: isize, dy: isize) -> Coord {
-        let x = self.x as isize;
-        let y = self.y as isize;
-        assert!(x + dx >= 0);
-        assert!(y + dy >= 0);
-        Coord {
-            x: (x + dx) as usize,
-            y: (y + dy) as usize,
-        }
-    }
-
-    fn diff(&self, other: &Coord) -> (isize, isize) {
-        (
-            other.x as isize - self.x as isize,
-            other.y as isize - self.y as isize,
-        )
+    /// The neighboring coordinate through `side`, or None if that's off the top or left edge
+    fn step(&self, side: Sides) -> Option<Coord> {
+        let (dx, dy) = match side {
+            Sides::N => (0, -1),
+            Sides::E => (1, 0),
+            Sides::S => (0, 1),
+            Sides::W => (-1, 0),
+            _ => return None,
+        };
+        Some(Coord {
+            x: self.x.checked_add_signed(dx)?,
+            y: self.y.checked_add_signed(dy)?,
+        })
     }
 }
 
 impl Field {
-    pub fn find_start(&self) -> Coord {
-        let i = self
+    pub fn find_start(&self) -> Result<Coord> {
+        let (i, _) = self
             .tiles
             .iter()
             .enumerate()
-            .find(|(_, t)| **t == Tile::Start)
-            .unwrap()
-            .0;
-        self.coord_from_index(i)
+            .filter(|(_, t)| **t == Tile::Start)
+            .exactly_one()
+            .map_err(|starts| format_err!("Expected 1 start tile, found {}", starts.count()))?;
+        Ok(self.coord_from_index(i))
+    }
+
+    // Tiles are indexed (x, y), so the grid's rows run along x
+    fn width(&self) -> usize {
+        self.tiles.rows()
+    }
+
+    fn height(&self) -> usize {
+        self.tiles.cols()
     }
 
     fn coord_from_index(&self, i: usize) -> Coord {
-        Coord::from((i % self.cols(), i / self.cols()))
+        Coord::from((i % self.width(), i / self.width()))
     }
 
     fn tile<C: Into<Coord>>(&self, coord: C) -> Tile {
@@ -105,152 +212,109 @@ impl Field {
         self.tiles[(coord.x, coord.y)]
     }
 
-    fn connected_neighbors(&self, coord: Coord) -> Option<(Coord, Coord)> {
-        let max_x = self.cols() - 1;
-        let max_y = self.rows() - 1;
+    /// The neighbor through `side`, if it's on the grid
+    fn neighbor(&self, coord: Coord, side: Sides) -> Option<Coord> {
+        let next = coord.step(side)?;
+        (next.x < self.width() && next.y < self.height()).then_some(next)
+    }
+
+    /// Sides of the start tile's hidden pipe: wherever a neighboring pipe opens back onto it
+    fn start_sides(&self, start: Coord) -> Sides {
+        Sides::EACH
+            .into_iter()
+            .filter(|&side| {
+                self.neighbor(start, side)
+                    .is_some_and(|n| self.tile(n).sides().contains(side.opposite()))
+            })
+            .fold(Sides::NONE, |sides, side| sides | side)
+    }
+
+    /// The pipe hidden under the start tile
+    fn start_tile(&self, start: Coord) -> Result<Tile> {
+        let sides = self.start_sides(start);
+        Tile::from_sides(sides).with_context(|| {
+            format!(
+                "Start tile at {start} connects to {} pipes instead of 2",
+                sides.len()
+            )
+        })
+    }
+
+    fn open_sides(&self, coord: Coord) -> Sides {
         match self.tile(coord) {
-            Tile::PipeNS if coord.y > 0 && coord.y < max_y => {
-                Some((coord.offset(0, 1), coord.offset(0, -1)))
-            }
-            Tile::PipeWE if coord.x > 0 && coord.x < max_x => {
-                Some((coord.offset(-1, 0), coord.offset(1, 0)))
-            }
-            Tile::PipeNE if coord.x < max_x && coord.y > 0 => {
-                Some((coord.offset(0, -1), coord.offset(1, 0)))
-            }
-            Tile::PipeSE if coord.x < max_x && coord.y < max_y => {
-                Some((coord.offset(0, 1), coord.offset(1, 0)))
-            }
-            Tile::PipeSW if coord.x > 0 && coord.y < max_y => {
-                Some((coord.offset(0, 1), coord.offset(-1, 0)))
-            }
-            Tile::PipeNW if coord.x > 0 && coord.y > 0 => {
-                Some((coord.offset(0, -1), coord.offset(-1, 0)))
-            }
-            Tile::Start => {
-                let mut offsets: Vec<(isize, isize)> = vec![];
-                if coord.x > 0 {
-                    offsets.push((-1, 0));
-                }
-                if coord.x < max_x {
-                    offsets.push((1, 0));
-                }
-                if coord.y > 0 {
-                    offsets.push((0, -1));
-                }
-                if coord.y < max_y {
-                    offsets.push((0, 1));
-                }
-                let coords: Option<(Coord, Coord)> = offsets
-                    .into_iter()
-                    .flat_map(|(dx, dy)| {
-                        let c = coord.offset(dx, dy);
-                        match self.tile(c) {
-                            Tile::Start => unreachable!("multiple start tiles?"),
-                            Tile::Ground => None,
-                            Tile::PipeNS if dy != 0 => Some(c),
-                            Tile::PipeWE if dx != 0 => Some(c),
-                            Tile::PipeNE if dy == 1 || dx == -1 => Some(c),
-                            Tile::PipeNW if dy == 1 || dx == 1 => Some(c),
-                            Tile::PipeSE if dy == -1 || dx == -1 => Some(c),
-                            Tile::PipeSW if dy == -1 || dx == 1 => Some(c),
-                            _ => None,
-                        }
-                    })
-                    .collect_tuple();
-                Some(coords.expect("Starting point doesn't connect to exactly 2 pipe tiles"))
-            }
-            Tile::Ground => None,
-            _ => unreachable!("Found a tile in a location where it can't be traversed"),
+            Tile::Start => self.start_sides(coord),
+            tile => tile.sides(),
         }
     }
 
+    /// The two tiles a pipe leads to, failing if either end is dangling
+    fn connected_neighbors(&self, coord: Coord) -> Result<(Coord, Coord)> {
+        let sides = self.open_sides(coord);
+        if sides.len() != 2 {
+            bail!("{} at {coord} is not a pipe", self.tile(coord));
+        }
+        sides
+            .iter()
+            .map(|side| {
+                self.neighbor(coord, side)
+                    .filter(|&n| self.open_sides(n).contains(side.opposite()))
+                    .with_context(|| format!("Pipe at {coord} is dangling on its {side:?} side"))
+            })
+            .collect::<Result<Vec<_>>>()
+            .map(|ends| (ends[0], ends[1]))
+    }
+
     // Returns the path of the loop
-    fn find_loop_path(&self) -> Vec<Coord> {
-        let start = self.find_start();
+    fn find_loop_path(&self) -> Result<Vec<Coord>> {
+        let start = self.find_start()?;
         let mut path = vec![start];
 
         // Pick one of the start-connected neighbors
-        let mut current = self.connected_neighbors(start).unwrap().0;
+        let mut current = self.connected_neighbors(start)?.0;
         let mut prev = start;
-        let mut next;
         while current != start {
             path.push(current);
-            // eprintln!("Iter: {}", path.iter().map(|c| c.to_string()).join(" -> "));
-            let connections = self
-                .connected_neighbors(current)
-                .expect("Pipes should always return 2 neighbors");
-
-            next = match connections {
-                (a, _) if a != prev => a,
-                (_, b) if b != prev => b,
-                (_, _) => unreachable!(
-                    "Neither of the connected neighbors is the previous traversed neighbor"
-                ),
+            // Every pipe connects back the way we came, so keep going out the other end
+            let next = match self.connected_neighbors(current)? {
+                (a, b) if a == prev => b,
+                (a, _) => a,
             };
             prev = current;
             current = next;
         }
-        // eprintln!("Path: {}", path.iter().map(|c| c.to_string()).join(" -> "));
-        path
-    }
-
-    fn remove_unused_pipes(&mut self) {
-        let loop_coords = self.find_loop_path();
-        let (a, start, b) = (
-            *(loop_coords.last().unwrap()),
-            loop_coords[0],
-            loop_coords[1],
-        );
-        let (cols, rows) = self.tiles.size();
+        Ok(path)
+    }
+
+    fn remove_unused_pipes(&mut self) -> Result<()> {
+        let loop_coords = self.find_loop_path()?;
+        let start = loop_coords[0];
+        let (rows, cols) = self.tiles.size();
         let items = vec![Tile::Ground; cols * rows];
         let mut grid = Grid::from_vec_with_order(items, cols, grid::Order::ColumnMajor);
         for c in loop_coords {
-            let tile = self.tiles[(c.x, c.y)];
-            grid[(c.x, c.y)] = tile;
+            grid[(c.x, c.y)] = self.tiles[(c.x, c.y)];
         }
-        let a_delta = start.diff(&a);
-        let b_delta = start.diff(&b);
-        grid[(start.x, start.y)] = match (a_delta, b_delta) {
-            ((0, _), (0, _)) => Tile::PipeNS,
-            ((_, 0), (_, 0)) => Tile::PipeWE,
-            ((1, 0), (0, 1)) | ((0, 1), (1, 0)) => Tile::PipeSE,
-            ((-1, 0), (0, 1)) | ((0, 1), (-1, 0)) => Tile::PipeSW,
-            ((1, 0), (0, -1)) | ((0, -1), (1, 0)) => Tile::PipeNE,
-            ((-1, 0), (0, -1)) | ((0, -1), (-1, 0)) => Tile::PipeNW,
-            _ => unreachable!("Start had neighbor deltas {:?} and {:?}", a_delta, b_delta),
-        };
-        self.tiles = grid
+        grid[(start.x, start.y)] = self.start_tile(start)?;
+        self.tiles = grid;
+        Ok(())
     }
 
-    fn count_enclosed_ground(&self) -> u32 {
-        let mut count = 0;
-        let mut crossed_wall = false;
-        let mut last_corner = None::<Tile>;
-
-        for row in self.tiles.iter_rows() {
-            crossed_wall = false;
-            last_corner = None;
-            for tile in row {
-                match (*tile, last_corner) {
-                    (Tile::Ground, _) if crossed_wall => count += 1,
-                    (Tile::Ground, _) => (),
-                    (Tile::PipeNS, _) => crossed_wall = !crossed_wall,
-
-                    (Tile::PipeNE, None) => last_corner = Some(Tile::PipeNE),
-                    (Tile::PipeNW, Some(Tile::PipeNE)) => last_corner = None,
-                    (Tile::PipeSW, Some(Tile::PipeNE)) => crossed_wall = !crossed_wall,
-
-                    (Tile::PipeSE, None) => last_corner = Some(Tile::PipeSE),
-                    (Tile::PipeNW, Some(Tile::PipeSW)) => crossed_wall = !crossed_wall,
-                    (Tile::PipeSW, Some(Tile::PipeSE)) => last_corner = None,
-
-                    (found, Some(after)) => unreachable!("Found {} after {}", found, after),
-                    (found, None) => unreachable!("Found {}", found),
+    // Scanning each row, crossing any tile that connects north flips whether we're inside the loop.
+    // Only counting north connections means a wall like L-7 is crossed once, and L-J is not crossed.
+    // Expects unused pipes to have already been removed.
+    fn enclosed_ground(&self) -> Vec<Coord> {
+        let mut enclosed = Vec::new();
+        for y in 0..self.height() {
+            let mut inside = false;
+            for x in 0..self.width() {
+                match self.tile((x, y)) {
+                    Tile::Ground if inside => enclosed.push(Coord { x, y }),
+                    Tile::PipeNS | Tile::PipeNE | Tile::PipeNW => inside = !inside,
+                    _ => (),
                 }
             }
         }
-        count
+        enclosed
     }
 }
 
@@ -306,6 +370,38 @@ mod test {
         assert_eq!(field.tile((3, 3)), Tile::PipeNW);
     }
 
+    #[test]
+    fn test_sides() {
+        assert_eq!(Sides::N.opposite(), Sides::S);
+        assert_eq!(Sides::W.opposite(), Sides::E);
+        assert_eq!(Tile::from_sides(Sides::S | Sides::E), Some(Tile::PipeSE));
+        assert_eq!(Tile::from_sides(Sides::N), None);
+        for tile in Tile::PIPES {
+            assert_eq!(Tile::from_sides(tile.sides()), Some(tile));
+        }
+    }
+
+    #[test]
+    fn test_start_tile() {
+        let field = Field::from_str(SAMPLE3).unwrap();
+        let start = field.find_start().unwrap();
+        assert_eq!(field.start_tile(start).unwrap(), Tile::PipeSE);
+        let field = Field::from_str(SAMPLE).unwrap();
+        assert_eq!(field.start_tile((1, 1).into()).unwrap(), Tile::PipeSE);
+    }
+
+    #[test]
+    fn test_malformed() {
+        // Start doesn't connect to anything
+        assert!(part1("...\n.S.\n...\n").is_err());
+        // Loop is broken by a dangling pipe
+        assert!(part1(".....\n.S-7.\n.|.|.\n.L-..\n.....\n").is_err());
+        // Loop runs off the edge
+        assert!(part1("S-7\n|.|\nL-|\n").is_err());
+        // No start tile
+        assert!(part1("F7\nLJ\n").is_err());
+    }
+
     #[test]
     fn test_part1() {
         assert_eq!(part1(SAMPLE).unwrap(), 4);
@@ -345,6 +441,30 @@ mod test {
         assert_eq!(part2(SAMPLE4).unwrap(), 4);
         assert_eq!(part2(SAMPLE5).unwrap(), 8);
     }
+
+    #[test]
+    fn test_enclosed_ground_matches_part2() {
+        for sample in [SAMPLE, SAMPLE2, SAMPLE4, SAMPLE5] {
+            let mut field = Field::from_str(sample).unwrap();
+            field.remove_unused_pipes().unwrap();
+            let scanned = field.enclosed_ground().len() as u64;
+            assert_eq!(scanned, part2(sample).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let images = render(SAMPLE4).unwrap();
+        assert_eq!(images.len(), 1);
+        let image = &images[0];
+        assert_eq!((image.width(), image.height()), (44, 36));
+        let cell = |x: usize, y: usize| image.pixel(x * 4, y * 4);
+        assert_eq!(cell(0, 0), render::BLACK);
+        assert_eq!(cell(1, 1), render::RED);
+        assert_eq!(cell(2, 1), render::BLUE);
+        assert_eq!(cell(2, 6), render::GREEN);
+        assert_eq!(cell(3, 3), render::BLACK);
+    }
 }
 
 #[cfg(feature = "bench")]
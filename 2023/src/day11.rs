@@ -1,30 +1,77 @@
 //! [Advent of Code Day 11](https://adventofcode.com/2023/day/11)
 
-use crate::math::manhattan_distance;
+use crate::math;
 use crate::parse;
 use crate::prelude::*;
+use crate::svg::Svg;
 use grid::Grid;
 
 pub fn part1(input: &str) -> Result<usize> {
-    let space = Universe::from_str(input)?;
-    Ok(space.pairwise_distance_sum(2))
+    distance_sum(input, 2)
 }
 
 pub fn part2(input: &str) -> Result<usize> {
-    let space = Universe::from_str(input)?;
-    Ok(space.pairwise_distance_sum(1_000_000))
+    distance_sum(input, 1_000_000)
 }
 
-#[derive(Debug, Clone, Deref)]
+/// Sum of distances between every pair of galaxies, when each empty row or column expands into
+/// `expansion` of them
+pub fn distance_sum(input: &str, expansion: usize) -> Result<usize> {
+    let space = Universe::from_str(input)?.with_expansion(expansion)?;
+    Ok(space.pairwise_distance_sum())
+}
+
+/// Draws the numbered galaxies after expansion, with the expanded empty rows and columns shaded
+pub fn svg(input: &str, gap_distance: usize) -> Result<Svg> {
+    const SIZE: f64 = 10.0;
+    let space = Universe::from_str(input)?.with_expansion(gap_distance)?;
+    let empty_cols = space.empty_cols();
+    let empty_rows = space.empty_rows();
+
+    // Where an original row or column ends up after the empty ones before it expand
+    let expand = |i: usize, empties: &[usize]| {
+        i + (gap_distance - 1) * empties.iter().filter(|e| **e < i).count()
+    };
+    // Grid is indexed (x, y), so its rows run along x
+    let width = expand(space.grid.rows(), &empty_cols) as f64 * SIZE;
+    let height = expand(space.grid.cols(), &empty_rows) as f64 * SIZE;
+    let gap = gap_distance as f64 * SIZE;
+
+    let mut svg = Svg::new(width, height);
+    svg.rect(0.0, 0.0, width, height, "black");
+    for col in &empty_cols {
+        svg.rect(expand(*col, &empty_cols) as f64 * SIZE, 0.0, gap, height, "#333");
+    }
+    for row in &empty_rows {
+        svg.rect(0.0, expand(*row, &empty_rows) as f64 * SIZE, width, gap, "#333");
+    }
+    for (i, (x, y)) in space.galaxy_locations().into_iter().enumerate() {
+        let (cx, cy) = ((x as f64 + 0.5) * SIZE, (y as f64 + 0.5) * SIZE);
+        svg.point(cx, cy, SIZE * 0.4, "gold")
+            .text(cx, cy, SIZE * 0.5, &(i + 1).to_string());
+    }
+    Ok(svg)
+}
+
+#[derive(Debug, Clone)]
 struct Universe {
     grid: Grid<Space>,
+    /// How many rows or columns each empty one becomes
+    expansion: usize,
 }
 
 impl Universe {
+    fn with_expansion(self, expansion: usize) -> Result<Universe> {
+        if expansion == 0 {
+            bail!("Empty space can't expand to nothing");
+        }
+        Ok(Universe { expansion, ..self })
+    }
+
     fn empty_rows(&self) -> Vec<usize> {
         self.grid
             .iter_cols()
-            .map(|col| col.map(|s| (*s as u32)).sum::<u32>() == 0)
+            .map(|col| col.map(|s| *s as u32).sum::<u32>() == 0)
             .enumerate()
             .filter(|(_, is_empty)| *is_empty)
             .map(|(i, _)| i)
@@ -34,43 +81,30 @@ impl Universe {
     fn empty_cols(&self) -> Vec<usize> {
         self.grid
             .iter_rows()
-            .map(|row| row.map(|s| (*s as u32)).sum::<u32>() == 0)
+            .map(|row| row.map(|s| *s as u32).sum::<u32>() == 0)
             .enumerate()
             .filter(|(_, is_empty)| *is_empty)
             .map(|(i, _)| i)
             .collect_vec()
     }
 
-    fn galaxy_locations(&self, gap_distance: usize) -> Vec<(usize, usize)> {
+    fn galaxy_locations(&self) -> Vec<(usize, usize)> {
         let empty_cols = self.empty_cols();
         let empty_rows = self.empty_rows();
+        let growth = self.expansion - 1;
         self.grid
             .indexed_iter()
             .filter(|(_, s)| **s == Space::Galaxy)
             .map(|((x, y), _)| {
-                let x_gaps = empty_cols
-                    .iter()
-                    .find_position(|col| **col > x)
-                    .map(|(pos, _)| pos)
-                    .unwrap_or_else(|| empty_cols.len());
-                let y_gaps = empty_rows
-                    .iter()
-                    .find_position(|row| **row > y)
-                    .map(|(pos, _)| pos)
-                    .unwrap_or_else(|| empty_rows.len());
-                let dx = (gap_distance - 1) * x_gaps;
-                let dy = (gap_distance - 1) * y_gaps;
-                (x + dx, y + dy)
+                let x_gaps = empty_cols.partition_point(|col| *col < x);
+                let y_gaps = empty_rows.partition_point(|row| *row < y);
+                (x + growth * x_gaps, y + growth * y_gaps)
             })
             .collect()
     }
 
-    fn pairwise_distance_sum(&self, gap_distance: usize) -> usize {
-        self.galaxy_locations(gap_distance)
-            .into_iter()
-            .combinations(2)
-            .map(|combo| manhattan_distance(combo[0], combo[1]))
-            .sum()
+    fn pairwise_distance_sum(&self) -> usize {
+        math::pairwise_manhattan_sum(&self.galaxy_locations())
     }
 }
 
@@ -95,7 +129,7 @@ impl FromStr for Universe {
 
     fn from_str(s: &str) -> Result<Self> {
         let grid = parse::parse_2d::<Space>(s)?;
-        Ok(Universe { grid })
+        Ok(Universe { grid, expansion: 2 })
     }
 }
 
@@ -120,8 +154,8 @@ mod test {
     #[test]
     fn test_parse() {
         let u = Universe::from_str(SAMPLE).unwrap();
-        assert_eq!(u[(3, 0)], Space::Galaxy);
-        assert_eq!(u[(0, 0)], Space::Empty);
+        assert_eq!(u.grid[(3, 0)], Space::Galaxy);
+        assert_eq!(u.grid[(0, 0)], Space::Empty);
     }
 
     #[test]
@@ -134,11 +168,30 @@ mod test {
     #[test]
     fn test_galaxy_locations() {
         let u = Universe::from_str(SAMPLE).unwrap();
-        let g = u.galaxy_locations(2);
+        let g = u.galaxy_locations();
         assert_eq!(g[0], (4, 0));
         assert_eq!(g[1], (9, 1));
         assert_eq!(g[3], (8, 5));
     }
+
+    #[test]
+    fn test_expansion() {
+        // Each extra row or column of expansion adds 82 across all the pairs
+        assert_eq!(distance_sum(SAMPLE, 1).unwrap(), 374 - 82);
+        assert!(distance_sum(SAMPLE, 0).is_err());
+    }
+
+    #[test]
+    fn test_svg() {
+        let doc = svg(SAMPLE, 2).unwrap().to_string();
+        assert!(doc.contains(r#"viewBox="0 0 130 120""#));
+        // Empty column 2 expands to 2 cells wide
+        assert!(doc.contains(r##"<rect x="20" y="0" width="20" height="120" fill="#333"/>"##));
+        // Galaxy 1 moves from (3, 0) to (4, 0)
+        assert!(doc.contains(r#"<circle cx="45" cy="5" r="4" fill="gold"/>"#));
+        assert_eq!(doc.matches("<circle").count(), 9);
+    }
+
     #[test]
     fn test_part1() {
         assert_eq!(part1(SAMPLE).unwrap(), 374);
@@ -146,9 +199,8 @@ mod test {
 
     #[test]
     fn test_part2() {
-        let u = Universe::from_str(SAMPLE).unwrap();
-        assert_eq!(u.pairwise_distance_sum(10), 1030);
-        assert_eq!(u.pairwise_distance_sum(100), 8410);
+        assert_eq!(distance_sum(SAMPLE, 10).unwrap(), 1030);
+        assert_eq!(distance_sum(SAMPLE, 100).unwrap(), 8410);
     }
 }
 
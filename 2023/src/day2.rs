@@ -1,12 +1,14 @@
 //! [Advent of Code Day 2](https://adventofcode.com/2023/day/2)
 
+use crate::counts::Counts;
 use crate::prelude::*;
+use parse_display::{Display, FromStr};
 
 static RE_GAME: Lazy<Regex> = Lazy::new(|| Regex::new(r"Game (\d*): (.*)").unwrap());
 
-/// Calculates the sum of game IDs that can be played with 12 red, 13 blue, & 14 green cubes
+/// Calculates the sum of game IDs that can be played with 12 red, 13 green, & 14 blue cubes
 pub fn part1(input: &str) -> Result<u32> {
-    let complete = CubeSet::new(12, 13, 14);
+    let complete = cubes(12, 13, 14);
     let games = crate::parse::parse_lines::<Game>(input)?;
     let sum = games
         .into_iter()
@@ -17,16 +19,40 @@ pub fn part1(input: &str) -> Result<u32> {
 }
 
 /// Calculates the power of the minimum set of cubes needed for a cube-drawing game
-pub fn part2(input: &str) -> Result<u32> {
+pub fn part2(input: &str) -> Result<u64> {
     let games = crate::parse::parse_lines::<Game>(input)?;
     let sum = games
         .iter()
         .map(Game::min_superset)
-        .map(|cs| cs.power())
+        .map(|cs| power(&cs))
         .sum();
     Ok(sum)
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Display, FromStr)]
+#[display(style = "lowercase")]
+pub enum Color {
+    Red,
+    Green,
+    Blue,
+}
+
+impl Color {
+    pub const ALL: [Color; 3] = [Color::Red, Color::Green, Color::Blue];
+}
+
+/// How many cubes of each color, parsed from strings like "3 red, 2 blue"
+pub type CubeSet = Counts<Color>;
+
+pub fn cubes(red: u32, green: u32, blue: u32) -> CubeSet {
+    Color::ALL.into_iter().zip([red, green, blue]).collect()
+}
+
+/// The power of a set of cubes as equal to the color counts multipled
+pub fn power(cubes: &CubeSet) -> u64 {
+    cubes.product(&Color::ALL)
+}
+
 #[derive(Debug, Clone)]
 pub struct Game {
     pub id: u32,
@@ -35,18 +61,14 @@ pub struct Game {
 
 impl Game {
     pub fn is_possible_with(&self, complete: &CubeSet) -> bool {
-        self.draws.iter().all(|d| d < complete)
+        self.draws.iter().all(|d| d.is_subset(complete))
     }
 
     // Min superset is the smallest cubeset where is_possible_with returns true
     pub fn min_superset(&self) -> CubeSet {
-        let mut min_set = self.draws[0];
-        for draw in self.draws.iter().skip(1) {
-            min_set.red = cmp::max(draw.red, min_set.red);
-            min_set.green = cmp::max(draw.green, min_set.green);
-            min_set.blue = cmp::max(draw.blue, min_set.blue);
-        }
-        min_set
+        self.draws
+            .iter()
+            .fold(CubeSet::new(), |min_set, draw| min_set.join(draw))
     }
 }
 
@@ -72,73 +94,6 @@ impl FromStr for Game {
     }
 }
 
-// Parse a string like: "3 red, 2 blue" into a CubeSet
-impl FromStr for CubeSet {
-    type Err = Error;
-
-    fn from_str(s: &str) -> Result<Self> {
-        let mut red = 0;
-        let mut green = 0;
-        let mut blue = 0;
-
-        let parts = s.split(',').map(str::trim);
-        for part in parts {
-            let frags: Vec<&str> = part.splitn(2, ' ').collect();
-            if frags.len() < 2 {
-                bail!("Expected count & color: {part}");
-            }
-            let count = frags[0].parse::<u32>()?;
-            let color = frags[1];
-            match color {
-                "red" => red = count,
-                "green" => green = count,
-                "blue" => blue = count,
-                _ => bail!("Unknown color: {color}"),
-            }
-        }
-        Ok(CubeSet { red, green, blue })
-    }
-}
-
-#[derive(PartialEq, Debug, Copy, Clone)]
-pub struct CubeSet {
-    pub red: u32,
-    pub green: u32,
-    pub blue: u32,
-}
-
-impl CubeSet {
-    pub fn new(red: u32, green: u32, blue: u32) -> CubeSet {
-        CubeSet { red, green, blue }
-    }
-
-    /// The power of a set of cubes as equal to the color counts multipled
-    pub fn power(&self) -> u32 {
-        self.red * self.green * self.blue
-    }
-}
-
-// Custom Partial Ordering to use comparison orders
-// Less implies strict subset; greater implies strict superset
-// CubeSet::new(1,2,3) < CubeSet::new(3,3,3)
-impl PartialOrd for CubeSet {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        let r = self.red.cmp(&other.red);
-        let g = self.green.cmp(&other.green);
-        let b = self.blue.cmp(&other.blue);
-        if r.is_eq() && g.is_eq() && b.is_eq() {
-            Some(Ordering::Equal)
-        } else if r.is_le() && g.is_le() && b.is_le() {
-            Some(Ordering::Less)
-        } else if r.is_ge() && g.is_ge() && b.is_ge() {
-            Some(Ordering::Greater)
-        } else {
-            // Not comparable
-            None
-        }
-    }
-}
-
 #[cfg(test)]
 mod test {
     use super::*;
@@ -148,16 +103,16 @@ mod test {
     #[allow(clippy::bool_assert_comparison)]
     fn test_cube_cmp() {
         // false comparisons don't necessarily imply the inverse comparison is true
-        assert_eq!(CubeSet::new(1, 2, 3), CubeSet::new(1, 2, 3));
-        assert_eq!(CubeSet::new(1, 2, 3) < CubeSet::new(1, 2, 3), false);
-        assert!(CubeSet::new(1, 2, 3) < CubeSet::new(2, 3, 4));
-        assert!(CubeSet::new(1, 2, 3) < CubeSet::new(1, 2, 4));
-        assert_eq!(CubeSet::new(1, 2, 3) < CubeSet::new(4, 4, 2), false);
-        assert_eq!(CubeSet::new(1, 2, 3) > CubeSet::new(1, 2, 3), false);
-        assert_eq!(CubeSet::new(1, 2, 3) > CubeSet::new(2, 3, 4), false);
-        assert_eq!(CubeSet::new(1, 2, 3) > CubeSet::new(1, 2, 4), false);
-        assert_eq!(CubeSet::new(1, 2, 3) > CubeSet::new(4, 4, 2), false);
-        assert!(CubeSet::new(5, 5, 5) > CubeSet::new(4, 5, 5));
+        assert_eq!(cubes(1, 2, 3), cubes(1, 2, 3));
+        assert_eq!(cubes(1, 2, 3) < cubes(1, 2, 3), false);
+        assert!(cubes(1, 2, 3) < cubes(2, 3, 4));
+        assert!(cubes(1, 2, 3) < cubes(1, 2, 4));
+        assert_eq!(cubes(1, 2, 3) < cubes(4, 4, 2), false);
+        assert_eq!(cubes(1, 2, 3) > cubes(1, 2, 3), false);
+        assert_eq!(cubes(1, 2, 3) > cubes(2, 3, 4), false);
+        assert_eq!(cubes(1, 2, 3) > cubes(1, 2, 4), false);
+        assert_eq!(cubes(1, 2, 3) > cubes(4, 4, 2), false);
+        assert!(cubes(5, 5, 5) > cubes(4, 5, 5));
     }
 
     #[test]
@@ -167,12 +122,9 @@ mod test {
 
         assert_eq!(game.id, 1);
         assert_eq!(game.draws.len(), 3);
-        assert_eq!(game.draws[0].red, 4);
-        assert_eq!(game.draws[0].green, 0);
-        assert_eq!(game.draws[0].blue, 3);
-        assert_eq!(game.draws[2].red, 0);
-        assert_eq!(game.draws[2].green, 2);
-        assert_eq!(game.draws[2].blue, 0);
+        assert_eq!(game.draws[0], cubes(4, 0, 3));
+        assert_eq!(game.draws[2], cubes(0, 2, 0));
+        assert!(Game::from_str("Game 1: 3 purple").is_err());
     }
 
     fn game(s: &str) -> Game {
@@ -190,23 +142,26 @@ mod test {
 
     #[test]
     fn test_game_possible() {
-        let complete = CubeSet::new(12, 13, 14);
+        let complete = cubes(12, 13, 14);
         let g = sample_games();
         assert!(g[0].is_possible_with(&complete));
         assert!(g[1].is_possible_with(&complete));
         assert!(!g[2].is_possible_with(&complete));
         assert!(!g[3].is_possible_with(&complete));
         assert!(g[4].is_possible_with(&complete));
+        // Drawing every cube at once is still possible
+        assert!(game("12 red, 13 green, 14 blue").is_possible_with(&complete));
     }
 
     #[test]
     fn test_min_set() {
         let g = sample_games();
-        assert_eq!(g[0].min_superset(), CubeSet::new(4, 2, 6));
-        assert_eq!(g[1].min_superset(), CubeSet::new(1, 3, 4));
-        assert_eq!(g[2].min_superset(), CubeSet::new(20, 13, 6));
-        assert_eq!(g[3].min_superset(), CubeSet::new(14, 3, 15));
-        assert_eq!(g[4].min_superset(), CubeSet::new(6, 3, 2));
+        assert_eq!(g[0].min_superset(), cubes(4, 2, 6));
+        assert_eq!(g[1].min_superset(), cubes(1, 3, 4));
+        assert_eq!(g[2].min_superset(), cubes(20, 13, 6));
+        assert_eq!(g[3].min_superset(), cubes(14, 3, 15));
+        assert_eq!(g[4].min_superset(), cubes(6, 3, 2));
+        assert_eq!(power(&game("3 red, 1 blue").min_superset()), 0);
     }
 
     const SAMPLE: &str = indoc! {"
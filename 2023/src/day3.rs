@@ -1,7 +1,8 @@
 //! [Advent of Code Day 3](https://adventofcode.com/2023/day/3)
 
 use crate::prelude::*;
-use regex::Match;
+use crate::scan::{Scanner, Span};
+use std::collections::BTreeMap;
 
 /// Calculates the sum of part numbers in the schematic
 pub fn part1(input: &str) -> Result<u32> {
@@ -18,100 +19,59 @@ pub fn part2(input: &str) -> Result<u32> {
 #[derive(Debug, Clone, PartialEq)]
 // Schematic that provides (x,y) matrix representation of the input
 // (0,0) is visually the top-left
-pub struct Schematic {
-    buf: String,
-    cols: usize,
-    rows: usize,
+pub struct Schematic<'a> {
+    scanner: Scanner<'a>,
 }
 
-static RE_NUM: Lazy<Regex> = Lazy::new(|| Regex::new(r"\d+").unwrap());
-static RE_SYM: Lazy<Regex> = Lazy::new(|| Regex::new(r"[^\w\.\s]+").unwrap());
+fn is_symbol(c: char) -> bool {
+    c != '.' && c.is_ascii_punctuation()
+}
 
-impl Schematic {
-    pub fn new(s: &str) -> Schematic {
-        let cols = s.lines().next().unwrap().len();
-        let rows = s.lines().count();
+impl<'a> Schematic<'a> {
+    pub fn new(s: &'a str) -> Schematic<'a> {
         Schematic {
-            rows,
-            cols,
-            buf: s.to_string(),
+            scanner: Scanner::new(s),
         }
     }
 
+    fn numbers(&self) -> impl Iterator<Item = Span<'a>> + '_ {
+        self.scanner.spans(|c| c.is_ascii_digit())
+    }
+
     pub fn part_numbers(&self) -> Vec<u32> {
-        let part_filter = |m: &Match| {
-            let (x, y) = (m.start() % (self.cols + 1), m.start() / (self.cols + 1));
-            self.has_adjacent_symbol(x, y, m.len())
-        };
-        let nums: Vec<u32> = RE_NUM
-            .find_iter(&self.buf)
-            .filter(part_filter)
-            .flat_map(|m| m.as_str().parse::<u32>())
-            .collect();
-        nums
+        self.numbers()
+            .filter(|span| self.has_adjacent_symbol(span.cols.start, span.row, span.cols.len()))
+            .flat_map(|span| span.text.parse::<u32>())
+            .collect()
     }
 
     pub fn gears(&self) -> Vec<Gear> {
-        let nums = RE_NUM
-            .find_iter(&self.buf)
-            .map(|m| Num {
-                x: m.start() % (self.cols + 1),
-                y: m.start() / (self.cols + 1),
-                len: m.as_str().len(),
-                val: m.as_str().parse().unwrap(),
-            })
-            .collect_vec();
-
-        let adjacent_parts = |sym_match: Match| {
-            nums.iter()
-                .filter(|p| {
-                    p.adjacent_to(
-                        sym_match.start() % (self.cols + 1),
-                        sym_match.start() / (self.cols + 1),
-                    )
-                })
-                .collect_vec()
-        };
-
-        RE_SYM
-            .find_iter(&self.buf)
-            // .inspect(|item| eprintln!("SYMBOL {}", item.as_str()))
-            .map(adjacent_parts)
-            // .inspect(|item| eprintln!("NUMS {:?}",item))
+        // Every number touching each symbol, keyed by the symbol's (row, col)
+        let mut adjacent_parts: BTreeMap<(usize, usize), Vec<u32>> = BTreeMap::new();
+        for span in self.numbers() {
+            let Ok(val) = span.text.parse() else {
+                continue;
+            };
+            for (pos, _) in self.scanner.around(&span).filter(|(_, c)| is_symbol(*c)) {
+                adjacent_parts.entry(pos).or_default().push(val);
+            }
+        }
+
+        adjacent_parts
+            .into_values()
             .filter(|p| p.len() == 2)
-            .map(|p| Gear(p[0].val, p[1].val))
+            .map(|p| Gear(p[0], p[1]))
             .collect()
     }
 
     fn has_symbol_at(&self, x: usize, y: usize) -> bool {
-        assert!(x < self.cols);
-        assert!(y < self.rows);
-
-        // buf has '\n' on each row
-        let width = self.cols + 1;
-
-        let offset = y * width + x;
-        let byte = self.buf.as_bytes()[offset];
-        let c = char::from_u32(byte as u32).unwrap();
-        c != '.' && c.is_ascii_punctuation()
+        self.scanner.get(y, x).is_some_and(is_symbol)
     }
 
     fn has_adjacent_symbol(&self, x: usize, y: usize, len: usize) -> bool {
-        assert!(x + len < self.cols + 1);
-        assert!(y < self.rows);
-        let x1 = x.saturating_sub(1);
-        let y1 = y.saturating_sub(1);
-        let x2 = cmp::min(x + len, self.cols - 1);
-        let y2 = cmp::min(y + 1, self.rows - 1);
-
-        for i in y1..=y2 {
-            for j in x1..=x2 {
-                if self.has_symbol_at(j, i) {
-                    return true;
-                }
-            }
-        }
-        false
+        self.scanner
+            .neighbors(y, x..x + len)
+            .any(|((y, x), _)| self.has_symbol_at(x, y))
     }
 }
 
@@ -130,25 +90,6 @@ impl PartialEq for Gear {
     }
 }
 
-#[derive(Debug, Copy, Clone)]
-struct Num {
-    val: u32,
-    x: usize,
-    y: usize,
-    len: usize,
-}
-
-impl Num {
-    fn adjacent_to(&self, x: usize, y: usize) -> bool {
-        let x1 = self.x.saturating_sub(1);
-        let y1 = self.y.saturating_sub(1);
-        let x2 = self.x.saturating_add(self.len);
-        let y2 = self.y.saturating_add(1);
-
-        x >= x1 && x <= x2 && y >= y1 && y <= y2
-    }
-}
-
 #[cfg(test)]
 mod test {
     use super::*;
@@ -213,6 +154,22 @@ mod test {
         assert_eq!(gears, vec![Gear(467, 35), Gear(755, 598)]);
     }
 
+    #[test]
+    fn test_crlf_and_ragged_lines() {
+        let crlf = SAMPLE.replace('\n', "\r\n");
+        assert_eq!(part1(&crlf).unwrap(), 4361);
+        assert_eq!(part2(&crlf).unwrap(), 467835);
+
+        let ragged = indoc! {"
+            12
+            .*....
+            3..
+            ..4
+        "};
+        assert_eq!(Schematic::new(ragged).part_numbers(), vec![12, 3]);
+        assert_eq!(part2(ragged).unwrap(), 36);
+    }
+
     #[test]
     fn test_part1() {
         assert_eq!(part1(SAMPLE).unwrap(), 4361);
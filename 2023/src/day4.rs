@@ -1,11 +1,12 @@
 //! [Advent of Code Day 4](https://adventofcode.com/2023/day/4)
 
 use crate::prelude::*;
+use crate::{dp, parse};
 use std::collections::BTreeSet;
 
 /// Calculates the total points for a set of scratchcards
 pub fn part1(input: &str) -> Result<u32> {
-    let cards = crate::parse::parse_lines::<Card>(input)?;
+    let cards = parse::parse_lines::<Card>(input)?;
     Ok(cards.iter().map(Card::points).sum())
 }
 
@@ -15,17 +16,11 @@ pub fn part2(input: &str) -> Result<u32> {
     Ok(counts.iter().sum())
 }
 
+/// Number of copies of each card: every copy of a card wins a copy of each of the next
+/// `matches()` cards, stopping at the end of the table
 fn card_counts(input: &str) -> Result<Vec<u32>> {
-    let cards = crate::parse::parse_lines::<Card>(input)?;
-    let mut card_counts = vec![1; cards.len()];
-
-    for (i, card) in cards.iter().enumerate() {
-        let original_points = card.matches();
-        for j in (i + 1)..=(i + original_points) {
-            card_counts[j] += card_counts[i];
-        }
-    }
-    Ok(card_counts)
+    let cards = parse::parse_lines::<Card>(input)?;
+    Ok(dp::cascade(&vec![1; cards.len()], |i| cards[i].matches()))
 }
 
 #[derive(Debug, Clone)]
@@ -49,27 +44,26 @@ impl Card {
     }
 }
 
-static RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\d+").unwrap());
-
 impl FromStr for Card {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self> {
-        let parts: Vec<&str> = s.split([':', '|']).skip(1).collect();
-        if parts.len() < 2 {
-            bail!("Invalid card: {s}");
-        }
-        let winners = RE
-            .find_iter(parts[0])
-            .map(|m| m.as_str().parse::<u32>())
-            .try_collect()?;
-
-        let numbers = RE
-            .find_iter(parts[1])
-            .map(|m| m.as_str().parse::<u32>())
-            .try_collect()?;
-
-        Ok(Card { winners, numbers })
+        let (label, rest) = s.split_once(':').context("Card is missing ':'")?;
+        let (winners, numbers) = rest
+            .split_once('|')
+            .with_context(|| format!("{label} is missing '|'"))?;
+        let unique = |part: &str, kind: &str| -> Result<BTreeSet<u32>> {
+            let nums: Vec<u32> = parse::extract_nums(part)?;
+            if let Some(n) = nums.iter().duplicates().next() {
+                bail!("{label} repeats {n} in its {kind}");
+            }
+            Ok(nums.into_iter().collect())
+        };
+
+        Ok(Card {
+            winners: unique(winners, "winning numbers")?,
+            numbers: unique(numbers, "numbers")?,
+        })
     }
 }
 
@@ -99,6 +93,14 @@ mod test {
         assert_eq!(card.numbers, BTreeSet::from([83, 86, 6, 31, 17, 9, 48, 53]));
     }
 
+    #[test]
+    fn test_parse_errors() {
+        assert!(Card::from_str("Card 1 41 48 | 83 86").is_err());
+        assert!(Card::from_str("Card 1: 41 48 83 86").is_err());
+        assert!(Card::from_str("Card 1: 41 48 41 | 83 86").is_err());
+        assert!(Card::from_str("Card 1: 41 48 | 83 86 83").is_err());
+    }
+
     #[test]
     fn test_points() {
         assert_eq!(sample_card(0).points(), 8);
@@ -114,6 +116,15 @@ mod test {
         assert_eq!(card_counts(SAMPLE).unwrap(), vec![1, 2, 4, 8, 14, 1])
     }
 
+    #[test]
+    fn test_wins_past_the_end() {
+        let cards = indoc! {"
+            Card 1: 1 2 3 | 1 2 3
+            Card 2: 4 5 6 | 4 5 6
+        "};
+        assert_eq!(card_counts(cards).unwrap(), vec![1, 2]);
+    }
+
     #[test]
     fn test_part1() {
         assert_eq!(part1(SAMPLE).unwrap(), 13);
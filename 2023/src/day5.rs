@@ -48,7 +48,7 @@ impl Almanac {
     /// Iterates through the maps to find the location ranges corresponding to any of the seed ranges
     /// where the seeds line specifies ranges: "seed_range_start seed_range_len seed_range_start etc."
     pub fn ranged_seed_locations(&self) -> Vec<Range<u32>> {
-        assert!(self.seeds.len() % 2 == 0);
+        assert!(self.seeds.len().is_multiple_of(2));
 
         let seeds: Vec<Range<u32>> = self
             .seeds
@@ -275,7 +275,7 @@ mod test {
 
         let sorted_dest = |range| {
             let mut res = map.dest_ranges(&[range]);
-            res.sort_by(|a, b| a.start.cmp(&b.start));
+            res.sort_by_key(|r| r.start);
             res
         };
 
@@ -6,6 +6,9 @@
 //! solve `x^2 -7x +9 = 0` which has two solutions: `x =~ 1.7` and `x =~ 5.3``.
 //! Every number between them will break the record.
 //! And it works for part 2 if you use a calculator that support 64-bit math.
+//!
+//! In code, the roots are found exactly with an integer square root instead of floating point,
+//! so whole-number roots (ties with the record) and huge part 2 races are handled without fudging.
 
 use crate::prelude::*;
 use crate::{math, parse};
@@ -16,8 +19,7 @@ pub fn part1(input: &str) -> Result<u64> {
     let races = Races::part1_from_str(input)?;
     let res = races
         .iter()
-        .map(Race::record_breaking_range)
-        .map(|range| range.count() as u64)
+        .map(Race::ways_to_win)
         .product();
 
     Ok(res)
@@ -26,9 +28,7 @@ pub fn part1(input: &str) -> Result<u64> {
 /// Calculate number of ways to win a massive race
 pub fn part2(input: &str) -> Result<u64> {
     let race = Race::part2_from_str(input)?;
-    let range = race.record_breaking_range();
-    let ways_to_win = range.count() as u64;
-    Ok(ways_to_win)
+    Ok(race.ways_to_win())
 }
 
 #[derive(Debug, Clone, Deref)]
@@ -70,17 +70,17 @@ impl Race {
     /// 0 = holdtime^2 - time*holdtime + distance
     /// ```
     ///
-    /// Solves using quadratic equation with: `a = 1; b = -time; c = distance`
-    pub fn record_breaking_range(&self) -> RangeInclusive<u64> {
-        let time = self.time as f64;
-        let distance = self.distance as f64;
-        let (root1, root2) = math::quadratic(1.0, -time, distance);
-
-        // We can't just tie the record, we have to beat it.
-        // So we add/sub an incredibly small value (1e-10) so that we can
-        // be sure to get the smallest integers above and below the solutions.
-        // This is needed for the cases where the quadratic solutions are whole numbers
-        ((root1 + 1e-10).ceil() as u64)..=((root2 - 1e-10).floor() as u64)
+    /// We can't just tie the record, we have to beat it, so this is the set of hold times where
+    /// `holdtime * (time - holdtime) > distance`. Returns None if the record can't be beaten.
+    pub fn record_breaking_range(&self) -> Option<RangeInclusive<u64>> {
+        math::product_above(self.time, self.distance)
+    }
+
+    /// Number of hold times that beat the record
+    pub fn ways_to_win(&self) -> u64 {
+        // Not `range.count()`, which is limited to usize and walks the whole range
+        self.record_breaking_range()
+            .map_or(0, |range| range.end() - range.start() + 1)
     }
 }
 
@@ -126,9 +126,27 @@ mod test {
     #[test]
     fn test_record_breaking_range() {
         let races = Races::part1_from_str(SAMPLE).unwrap();
-        assert_eq!(races[0].record_breaking_range(), 2..=5);
-        assert_eq!(races[1].record_breaking_range(), 4..=11);
-        assert_eq!(races[2].record_breaking_range(), 11..=19);
+        assert_eq!(races[0].record_breaking_range(), Some(2..=5));
+        assert_eq!(races[1].record_breaking_range(), Some(4..=11));
+        assert_eq!(races[2].record_breaking_range(), Some(11..=19));
+    }
+
+    #[test]
+    fn test_unbeatable_record() {
+        let race = Race::new(4, 4);
+        assert_eq!(race.record_breaking_range(), None);
+        assert_eq!(race.ways_to_win(), 0);
+    }
+
+    #[test]
+    fn test_large_race() {
+        // The record is tied at exactly 10^9 and 3 * 10^9, which f64 rounding can get wrong
+        let race = Race::new(4_000_000_000, 3_000_000_000_000_000_000);
+        assert_eq!(
+            race.record_breaking_range(),
+            Some(1_000_000_001..=2_999_999_999)
+        );
+        assert_eq!(race.ways_to_win(), 1_999_999_999);
     }
 
     #[test]
@@ -1,4 +1,8 @@
 //! [Advent of Code Day 7](https://adventofcode.com/2023/day/7)
+//!
+//! Hands are parsed once, then scored under a [`Ruleset`] which decides the card order, which cards
+//! are wild, which hand categories exist and how ties between them are broken. Part 1 and part 2
+//! are the same game under different rules.
 
 use crate::prelude::*;
 use std::collections::HashMap;
@@ -6,24 +10,23 @@ use std::collections::HashMap;
 /// Calculate winnings for Camel Cards hands based on bid
 pub fn part1(input: &str) -> Result<u32> {
     let camel_cards = CamelCards::parse(input)?;
-    Ok(camel_cards.winnings())
+    camel_cards.winnings(&Ruleset::standard())
 }
 
 /// Calculate winnings for Camel Cards hands based on bid using 'J' as Joker
 pub fn part2(input: &str) -> Result<u32> {
-    let camel_cards = CamelCards::parse_with_jokers(input)?;
-    Ok(camel_cards.winnings())
+    let camel_cards = CamelCards::parse(input)?;
+    camel_cards.winnings(&Ruleset::jokers())
 }
 
 #[derive(Debug, Clone, Deref)]
 /// Represents all hands and bids from a Camel Cards game
 pub struct CamelCards(Vec<HandBid>);
 
-#[derive(Debug, Clone, Copy, Eq)]
-/// A hand of 5 cards
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A hand of 5 cards, in the order they were dealt
 ///
-/// Note: Hand comparison is based on rank
-/// use `Hand::cards` to compare actual cards
+/// Hands have no order of their own, use `Ruleset::cmp` to compare them
 pub struct Hand([Card; 5]);
 
 impl Hand {
@@ -31,42 +34,12 @@ impl Hand {
     pub fn cards(&self) -> [Card; 5] {
         self.0
     }
-
-    /// Calculates the rank of a hand
-    pub fn rank(&self) -> Rank {
-        let mut map = HashMap::new();
-        let mut jokers = 0;
-        for card in self.0 {
-            match card {
-                Card::Joker => jokers += 1,
-                card => {
-                    let entry = map.entry(card).or_insert(0);
-                    *entry += 1;
-                }
-            }
-        }
-        let mut counts = map.into_values().collect_vec();
-        counts.sort();
-        counts.reverse();
-
-        // This can happen if we have 5 jokers
-        if counts.is_empty() {
-            counts.push(0);
-        }
-
-        counts[0] += jokers;
-        match (counts[0], counts.get(1)) {
-            (5, _) => Rank::FiveOfKind,
-            (4, _) => Rank::FourOfKind,
-            (3, Some(2)) => Rank::FullHouse,
-            (3, _) => Rank::ThreeOfKind,
-            (2, Some(2)) => Rank::TwoPair,
-            (2, _) => Rank::Pair,
-            _ => Rank::HighCard,
-        }
-    }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Single card, identified by the character on its face
+pub struct Card(pub char);
+
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Ord)]
 /// Ordered ranking of Camel Cards hands
 pub enum Rank {
@@ -79,110 +52,176 @@ pub enum Rank {
     FiveOfKind,
 }
 
-#[derive(Debug, Clone, Copy)]
-/// Represents a single hand and its bid
-pub struct HandBid {
-    pub hand: Hand,
-    pub bid: u32,
+impl Rank {
+    /// Sizes of the groups of matching cards needed for the category, largest first
+    pub fn shape(&self) -> &'static [usize] {
+        match self {
+            Rank::HighCard => &[],
+            Rank::Pair => &[2],
+            Rank::TwoPair => &[2, 2],
+            Rank::ThreeOfKind => &[3],
+            Rank::FullHouse => &[3, 2],
+            Rank::FourOfKind => &[4],
+            Rank::FiveOfKind => &[5],
+        }
+    }
 }
 
-impl CamelCards {
-    /// Calculates the winnings for a full list of hands and their bids
-    pub fn winnings(&self) -> u32 {
-        let mut hand_bids = self.0.clone();
-        hand_bids.sort_by_key(|hb| hb.hand);
-        hand_bids
-            .iter()
-            .enumerate()
-            .map(|(i, hb)| (i as u32 + 1) * hb.bid)
-            .sum()
-    }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// How hands of the same category are ordered
+pub enum Tiebreak {
+    /// Compare cards one at a time in the order they were dealt
+    Dealt,
+    /// Compare the strongest card of each hand, then the next strongest, and so on
+    Strongest,
 }
 
-impl PartialEq for Hand {
-    fn eq(&self, other: &Self) -> bool {
-        self.rank() == other.rank()
-    }
+#[derive(Debug, Clone, PartialEq)]
+/// The rules that decide how hands are ranked
+pub struct Ruleset {
+    /// Card faces from weakest to strongest
+    pub order: Vec<char>,
+    /// Cards that stand in for whatever makes the hand's category strongest
+    pub wildcards: Vec<char>,
+    /// Hand categories from weakest to strongest. Hands that fit none get the weakest.
+    pub categories: Vec<Rank>,
+    pub tiebreak: Tiebreak,
 }
 
-impl Ord for Hand {
-    fn cmp(&self, other: &Self) -> Ordering {
-        match self.rank().cmp(&other.rank()) {
-            Ordering::Equal => {
-                for i in 0..5 {
-                    match self.0[i].cmp(&other.0[i]) {
-                        Ordering::Equal => continue,
-                        ordering => {
-                            return ordering;
-                        }
-                    }
-                }
-                Ordering::Equal
+impl Ruleset {
+    /// Part 1 rules: no wildcards and J is a jack
+    pub fn standard() -> Ruleset {
+        Ruleset {
+            order: "23456789TJQKA".chars().collect(),
+            wildcards: vec![],
+            categories: vec![
+                Rank::HighCard,
+                Rank::Pair,
+                Rank::TwoPair,
+                Rank::ThreeOfKind,
+                Rank::FullHouse,
+                Rank::FourOfKind,
+                Rank::FiveOfKind,
+            ],
+            tiebreak: Tiebreak::Dealt,
+        }
+    }
+
+    /// Part 2 rules: J is a joker, which is wild and the weakest card
+    pub fn jokers() -> Ruleset {
+        Ruleset::standard().with_wildcard('J')
+    }
+
+    /// Makes `card` wild, and moves it to the bottom of the card order
+    pub fn with_wildcard(mut self, card: char) -> Ruleset {
+        self.order.retain(|c| *c != card);
+        self.order.insert(0, card);
+        self.wildcards.push(card);
+        self
+    }
+
+    /// Strength of a card, from 0 for the weakest
+    pub fn strength(&self, card: Card) -> Result<usize> {
+        self.order
+            .iter()
+            .position(|c| *c == card.0)
+            .with_context(|| format!("Unsupported card character '{}'", card.0))
+    }
+
+    /// Category of a hand
+    ///
+    /// Matching cards are grouped largest first, and the wildcards are added to whichever groups
+    /// come up short of a category's shape. The strongest category the wildcards can complete wins.
+    pub fn rank(&self, hand: &Hand) -> Rank {
+        let mut groups: HashMap<Card, usize> = HashMap::new();
+        let mut wild = 0;
+        for card in hand.cards() {
+            if self.wildcards.contains(&card.0) {
+                wild += 1;
+            } else {
+                *groups.entry(card).or_default() += 1;
             }
-            ordering => ordering,
         }
+        let counts = groups.into_values().sorted().rev().collect_vec();
+
+        let wild_needed = |rank: &Rank| -> usize {
+            rank.shape()
+                .iter()
+                .enumerate()
+                .map(|(i, size)| size.saturating_sub(counts.get(i).copied().unwrap_or(0)))
+                .sum()
+        };
+        self.categories
+            .iter()
+            .rev()
+            .find(|rank| wild_needed(rank) <= wild)
+            .or(self.categories.first())
+            .copied()
+            .unwrap_or(Rank::HighCard)
     }
-}
 
-impl PartialOrd for Hand {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+    /// Sort key for a hand: its category's place in the ruleset, then its tiebreaking strengths
+    fn key(&self, hand: &Hand) -> Result<(usize, Vec<usize>)> {
+        let rank = self.rank(hand);
+        let category = self.categories.iter().position(|r| *r == rank).unwrap_or(0);
+        let mut strengths: Vec<usize> = hand.cards().iter().map(|c| self.strength(*c)).try_collect()?;
+        if self.tiebreak == Tiebreak::Strongest {
+            strengths.sort_unstable_by(|a, b| b.cmp(a));
+        }
+        Ok((category, strengths))
+    }
+
+    /// Compares two hands under these rules
+    pub fn cmp(&self, a: &Hand, b: &Hand) -> Result<Ordering> {
+        Ok(self.key(a)?.cmp(&self.key(b)?))
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Copy, Hash)]
-/// Single Card. Joker is low (when not wild)
-pub enum Card {
-    Joker,
-    _2,
-    _3,
-    _4,
-    _5,
-    _6,
-    _7,
-    _8,
-    _9,
-    T,
-    J,
-    Q,
-    K,
-    A,
+#[derive(Debug, Clone, Copy)]
+/// Represents a single hand and its bid
+pub struct HandBid {
+    pub hand: Hand,
+    pub bid: u32,
 }
 
-static RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(\w{5}) (\d+)").unwrap());
 impl CamelCards {
-    pub fn parse(s: &str) -> Result<Self> {
-        CamelCards::parse_with(s, Hand::parse)
-    }
-
-    pub fn parse_with_jokers(s: &str) -> Result<Self> {
-        CamelCards::parse_with(s, Hand::parse_with_jokers)
+    /// Calculates the winnings for a full list of hands and their bids under a set of rules
+    pub fn winnings(&self, ruleset: &Ruleset) -> Result<u32> {
+        let mut keyed: Vec<_> = self
+            .iter()
+            .map(|hb| Ok((ruleset.key(&hb.hand)?, hb.bid)))
+            .collect::<Result<_>>()?;
+        // Stable, so identical hands stay in the order they were dealt
+        keyed.sort_by(|(a, _), (b, _)| a.cmp(b));
+        let winnings = keyed
+            .iter()
+            .enumerate()
+            .map(|(i, (_, bid))| (i as u32 + 1) * bid)
+            .sum();
+        Ok(winnings)
     }
+}
 
-    fn parse_with(s: &str, hand_parser_fn: impl Fn(&str) -> Result<Hand>) -> Result<CamelCards> {
+static RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(\S{5}) (\d+)").unwrap());
+impl CamelCards {
+    pub fn parse(s: &str) -> Result<Self> {
         let hands = RE
             .captures_iter(s)
             .map(|cap| {
-                let hand = hand_parser_fn(&cap[1]).unwrap();
-                let bid = cap[2].parse::<u32>().unwrap();
-                HandBid { hand, bid }
+                let hand = cap[1].parse()?;
+                let bid = cap[2].parse::<u32>()?;
+                Ok(HandBid { hand, bid })
             })
-            .collect_vec();
+            .collect::<Result<_>>()?;
         Ok(CamelCards(hands))
     }
 }
 
-impl Hand {
-    pub fn parse(s: &str) -> Result<Hand> {
-        Hand::parse_with(s, Card::new)
-    }
-
-    pub fn parse_with_jokers(s: &str) -> Result<Hand> {
-        Hand::parse_with(s, Card::new_with_joker)
-    }
+impl FromStr for Hand {
+    type Err = Error;
 
-    fn parse_with(s: &str, parse_fn: impl Fn(char) -> Card) -> Result<Hand> {
-        let cards = s.chars().map(parse_fn).collect_vec();
+    fn from_str(s: &str) -> Result<Hand> {
+        let cards = s.chars().map(Card).collect_vec();
         let len = cards.len();
         let hand: [Card; 5] = cards
             .try_into()
@@ -191,34 +230,6 @@ impl Hand {
     }
 }
 
-impl Card {
-    pub fn new(c: char) -> Card {
-        match c {
-            'A' => Card::A,
-            'K' => Card::K,
-            'Q' => Card::Q,
-            'J' => Card::J,
-            'T' => Card::T,
-            '9' => Card::_9,
-            '8' => Card::_8,
-            '7' => Card::_7,
-            '6' => Card::_6,
-            '5' => Card::_5,
-            '4' => Card::_4,
-            '3' => Card::_3,
-            '2' => Card::_2,
-            _ => panic!("Unsupported card character '{c}'"),
-        }
-    }
-
-    pub fn new_with_joker(c: char) -> Card {
-        match c {
-            'J' => Card::Joker,
-            c => Card::new(c),
-        }
-    }
-}
-
 #[cfg(test)]
 mod test {
     use super::*;
@@ -238,50 +249,73 @@ mod test {
         assert_eq!(cc.len(), 5);
         assert_eq!(
             cc[0].hand.cards(),
-            [Card::_3, Card::_2, Card::T, Card::_3, Card::K]
+            [Card('3'), Card('2'), Card('T'), Card('3'), Card('K')]
         );
         assert_eq!(cc[0].bid, 765);
+        assert!("AAAA".parse::<Hand>().is_err());
     }
 
     fn hand(cards: &str) -> Hand {
-        Hand::parse(cards).unwrap()
+        cards.parse().unwrap()
     }
 
     #[test]
     fn test_rank() {
-        assert_eq!(hand("AAAAA").rank(), Rank::FiveOfKind);
-        assert_eq!(hand("AA8AA").rank(), Rank::FourOfKind);
-        assert_eq!(hand("23332").rank(), Rank::FullHouse);
-        assert_eq!(hand("TTT98").rank(), Rank::ThreeOfKind);
-        assert_eq!(hand("23432").rank(), Rank::TwoPair);
-        assert_eq!(hand("A23A4").rank(), Rank::Pair);
-        assert_eq!(hand("23456").rank(), Rank::HighCard);
-    }
-
-    fn with_jokers(cards: &str) -> Hand {
-        Hand::parse_with_jokers(cards).unwrap()
+        let rules = Ruleset::standard();
+        assert_eq!(rules.rank(&hand("AAAAA")), Rank::FiveOfKind);
+        assert_eq!(rules.rank(&hand("AA8AA")), Rank::FourOfKind);
+        assert_eq!(rules.rank(&hand("23332")), Rank::FullHouse);
+        assert_eq!(rules.rank(&hand("TTT98")), Rank::ThreeOfKind);
+        assert_eq!(rules.rank(&hand("23432")), Rank::TwoPair);
+        assert_eq!(rules.rank(&hand("A23A4")), Rank::Pair);
+        assert_eq!(rules.rank(&hand("23456")), Rank::HighCard);
     }
 
     #[test]
     fn test_rank_with_jokers() {
-        assert_eq!(with_jokers("32T3K").rank(), Rank::Pair);
-        assert_eq!(with_jokers("T55J5").rank(), Rank::FourOfKind);
-        assert_eq!(with_jokers("KK677").rank(), Rank::TwoPair);
-        assert_eq!(with_jokers("KTJJT").rank(), Rank::FourOfKind);
-        assert_eq!(with_jokers("QQQJA").rank(), Rank::FourOfKind);
-        assert_eq!(with_jokers("JJJJJ").rank(), Rank::FiveOfKind);
+        let rules = Ruleset::jokers();
+        assert_eq!(rules.rank(&hand("32T3K")), Rank::Pair);
+        assert_eq!(rules.rank(&hand("T55J5")), Rank::FourOfKind);
+        assert_eq!(rules.rank(&hand("KK677")), Rank::TwoPair);
+        assert_eq!(rules.rank(&hand("KTJJT")), Rank::FourOfKind);
+        assert_eq!(rules.rank(&hand("QQQJA")), Rank::FourOfKind);
+        assert_eq!(rules.rank(&hand("JJJJJ")), Rank::FiveOfKind);
+        assert_eq!(rules.rank(&hand("2J3J4")), Rank::ThreeOfKind);
+        assert_eq!(rules.rank(&hand("22J33")), Rank::FullHouse);
     }
 
     #[test]
     fn test_hand_ordering() {
+        let rules = Ruleset::standard();
         let cc = CamelCards::parse(SAMPLE).unwrap();
         let mut hands = cc.iter().map(|hb| hb.hand).collect_vec();
-        hands.sort();
-        assert_eq!(hands[0].cards(), hand("32T3K").cards());
-        assert_eq!(hands[1].cards(), hand("KTJJT").cards());
-        assert_eq!(hands[2].cards(), hand("KK677").cards());
-        assert_eq!(hands[3].cards(), hand("T55J5").cards());
-        assert_eq!(hands[4].cards(), hand("QQQJA").cards());
+        hands.sort_by(|a, b| rules.cmp(a, b).unwrap());
+        assert_eq!(hands[0], hand("32T3K"));
+        assert_eq!(hands[1], hand("KTJJT"));
+        assert_eq!(hands[2], hand("KK677"));
+        assert_eq!(hands[3], hand("T55J5"));
+        assert_eq!(hands[4], hand("QQQJA"));
+    }
+
+    #[test]
+    fn test_variants() {
+        // A deck with two kinds of joker, both wild and weakest
+        let rules = Ruleset::jokers().with_wildcard('*');
+        assert_eq!(rules.rank(&hand("*J2K3")), Rank::ThreeOfKind);
+        assert_eq!(rules.cmp(&hand("*2345"), &hand("J2345")).unwrap(), Ordering::Less);
+
+        // Poker style tiebreaks compare the best cards first
+        let mut rules = Ruleset::standard();
+        assert_eq!(rules.cmp(&hand("2345A"), &hand("K2345")).unwrap(), Ordering::Less);
+        rules.tiebreak = Tiebreak::Strongest;
+        assert_eq!(rules.cmp(&hand("2345A"), &hand("K2345")).unwrap(), Ordering::Greater);
+
+        // Without full houses, they count as three of a kind
+        rules.categories.retain(|r| *r != Rank::FullHouse);
+        assert_eq!(rules.rank(&hand("23332")), Rank::ThreeOfKind);
+
+        let cc = CamelCards::parse("2345X 1").unwrap();
+        assert!(cc.winnings(&Ruleset::standard()).is_err());
     }
 
     #[test]
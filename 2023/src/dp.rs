@@ -0,0 +1,66 @@
+//! Dynamic programming helpers
+
+use std::ops::{AddAssign, SubAssign};
+
+/// Propagates values forward through a list, where each item adds its final value to the items
+/// after it
+///
+/// Item `i` starts at `initial[i]`, then once everything before it has been added in, its value is
+/// added to each of the next `reach(i)` items. Items past the end of the list are ignored. A
+/// running total spreads each contribution in constant time, so this is O(n) however far items
+/// reach.
+///
+/// ```
+/// # use aoc::dp::cascade;
+/// // Every item passes its total on to the next two
+/// assert_eq!(cascade(&[1, 1, 1, 1], |_| 2), vec![1, 2, 4, 7]);
+/// ```
+pub fn cascade<T>(initial: &[T], reach: impl Fn(usize) -> usize) -> Vec<T>
+where
+    T: Copy + Default + AddAssign + SubAssign,
+{
+    let n = initial.len();
+    let mut values = initial.to_vec();
+    // Running total of contributions into the current item, and how much of it stops at each item
+    let mut incoming = T::default();
+    let mut expiring = vec![T::default(); n + 1];
+    for i in 0..n {
+        incoming -= expiring[i];
+        values[i] += incoming;
+        let end = (i + 1).saturating_add(reach(i)).min(n);
+        if end > i + 1 {
+            incoming += values[i];
+            expiring[end] += values[i];
+        }
+    }
+    values
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_cascade() {
+        assert_eq!(
+            cascade(&[1, 1, 1, 1, 1, 1], |i| [4, 2, 2, 1, 0, 0][i]),
+            vec![1, 2, 4, 8, 14, 1]
+        );
+        assert!(cascade::<u32>(&[], |_| 1).is_empty());
+        assert_eq!(cascade(&[3, 0, 5], |_| 0), vec![3, 0, 5]);
+    }
+
+    #[test]
+    fn test_cascade_clamps_reach() {
+        assert_eq!(cascade(&[1, 1, 1], |_| 10), vec![1, 2, 4]);
+        assert_eq!(cascade(&[1u64, 1], |_| usize::MAX), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_cascade_signed() {
+        assert_eq!(
+            cascade(&[2i64, -1, 0], |i| if i == 0 { 2 } else { 0 }),
+            vec![2, 1, 2]
+        );
+    }
+}
@@ -0,0 +1,135 @@
+//! Lattice polygon measurements
+//!
+//! A polygon is a `Vec` (or slice) of vertices in order around its boundary, with an implied edge
+//! from the last vertex back to the first. Vertices have integer coordinates, so areas are kept
+//! doubled to stay exact.
+
+use num::integer::gcd;
+
+pub type Vertex = (i64, i64);
+
+/// Pairs of consecutive vertices, including the edge that closes the polygon
+fn edges(vertices: &[Vertex]) -> impl Iterator<Item = (Vertex, Vertex)> + '_ {
+    vertices
+        .iter()
+        .copied()
+        .zip(vertices.iter().copied().cycle().skip(1))
+}
+
+/// Twice the signed area, by the shoelace formula: positive if the vertices run counterclockwise
+/// (with y pointing up)
+pub fn signed_double_area(vertices: &[Vertex]) -> i64 {
+    edges(vertices)
+        .map(|((x0, y0), (x1, y1))| x0 * y1 - x1 * y0)
+        .sum()
+}
+
+/// Twice the area enclosed by the polygon
+pub fn double_area(vertices: &[Vertex]) -> u64 {
+    signed_double_area(vertices).unsigned_abs()
+}
+
+/// Area enclosed by the polygon
+pub fn area(vertices: &[Vertex]) -> f64 {
+    double_area(vertices) as f64 / 2.0
+}
+
+/// Length of the boundary
+pub fn perimeter(vertices: &[Vertex]) -> f64 {
+    edges(vertices)
+        .map(|((x0, y0), (x1, y1))| ((x1 - x0) as f64).hypot((y1 - y0) as f64))
+        .sum()
+}
+
+/// Number of lattice points on the boundary
+///
+/// An edge spanning `(dx, dy)` passes through `gcd(dx, dy)` lattice points, counting one end.
+/// For polygons with only horizontal and vertical edges this equals the perimeter.
+pub fn boundary_points(vertices: &[Vertex]) -> u64 {
+    edges(vertices)
+        .map(|((x0, y0), (x1, y1))| gcd(x1 - x0, y1 - y0).unsigned_abs())
+        .sum()
+}
+
+/// Number of lattice points strictly inside the polygon
+///
+/// Pick's theorem relates the area to the interior (`I`) and boundary (`B`) lattice points:
+/// `A = I + B/2 - 1`, so `I = (2A - B + 2) / 2`. A polygon with no area, such as a point or a
+/// line traced out and back, has no interior.
+pub fn interior_points(vertices: &[Vertex]) -> u64 {
+    let double_area = double_area(vertices);
+    if double_area == 0 {
+        return 0;
+    }
+    (double_area + 2).saturating_sub(boundary_points(vertices)) / 2
+}
+
+/// Number of lattice points inside or on the boundary of the polygon
+///
+/// By Pick's theorem this is `I + B = A + B/2 + 1`. That also holds for a polygon with no area,
+/// whose boundary retraces itself and so counts each lattice point twice.
+pub fn lattice_points(vertices: &[Vertex]) -> u64 {
+    if vertices.is_empty() {
+        return 0;
+    }
+    (double_area(vertices) + boundary_points(vertices)) / 2 + 1
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SQUARE: [Vertex; 4] = [(0, 0), (4, 0), (4, 4), (0, 4)];
+
+    #[test]
+    fn test_area() {
+        assert_eq!(signed_double_area(&SQUARE), 32);
+        let clockwise: Vec<Vertex> = SQUARE.iter().rev().copied().collect();
+        assert_eq!(signed_double_area(&clockwise), -32);
+        assert_eq!(area(&clockwise), 16.0);
+        assert_eq!(double_area(&[(0, 0), (3, 0), (0, 1)]), 3);
+    }
+
+    #[test]
+    fn test_perimeter() {
+        assert_eq!(perimeter(&SQUARE), 16.0);
+        assert_eq!(perimeter(&[(0, 0), (3, 0), (3, 4)]), 12.0);
+        assert_eq!(boundary_points(&SQUARE), 16);
+        assert_eq!(boundary_points(&[(0, 0), (6, 0), (0, 3)]), 6 + 3 + 3);
+    }
+
+    #[test]
+    fn test_pick() {
+        assert_eq!(interior_points(&SQUARE), 9);
+        assert_eq!(lattice_points(&SQUARE), 25);
+        // An L shape, with a vertex repeated and collinear points along the edges
+        let shape = [
+            (0, 0),
+            (2, 0),
+            (4, 0),
+            (4, 2),
+            (2, 2),
+            (2, 4),
+            (0, 4),
+            (0, 0),
+        ];
+        assert_eq!(double_area(&shape), 24);
+        assert_eq!(interior_points(&shape), 5);
+        assert_eq!(lattice_points(&shape), 21);
+        assert_eq!(interior_points(&[(0, 0), (5, 5)]), 0);
+    }
+
+    #[test]
+    fn test_degenerate() {
+        let collinear = [(0, 0), (2, 0), (4, 0)];
+        assert_eq!(double_area(&collinear), 0);
+        assert_eq!(boundary_points(&collinear), 8);
+        assert_eq!(interior_points(&collinear), 0);
+        assert_eq!(lattice_points(&collinear), 5);
+
+        assert_eq!(interior_points(&[(3, 3)]), 0);
+        assert_eq!(lattice_points(&[(3, 3)]), 1);
+        assert_eq!(interior_points(&[(1, 1), (1, 1), (1, 1)]), 0);
+        assert_eq!(lattice_points(&[]), 0);
+    }
+}
@@ -1,9 +1,16 @@
 use anyhow::Context;
 use serde_json::{json, Value};
 
+pub mod counts;
+pub mod dp;
+pub mod geometry;
 pub mod math;
 pub mod parse;
+pub mod render;
+pub mod scan;
+pub mod svg;
 
+#[allow(unused)]
 pub(crate) mod prelude {
     pub use anyhow::{bail, format_err, Chain, Context, Error, Result};
     pub use derive_deref::Deref;
@@ -66,6 +73,32 @@ macro_rules! bench_day {
 // Simply specify the days that are implemented
 handle_days![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11];
 
+/// Renders a puzzle's grid state as image frames, for the days that support it
+pub fn render(input: Input) -> anyhow::Result<Vec<render::Image>> {
+    match input.day {
+        10 => day10::render(&input.input),
+        _ => anyhow::bail!("Day {} does not support rendering", input.day),
+    }
+}
+
+/// Answers a puzzle with a custom expansion factor, for the days that support it
+pub fn expansion(input: Input, factor: usize) -> anyhow::Result<Value> {
+    match input.day {
+        11 => Ok(json!(day11::distance_sum(&input.input, factor)?)),
+        _ => anyhow::bail!("Day {} does not support an expansion factor", input.day),
+    }
+}
+
+/// Draws a puzzle's geometry as an SVG, for the days that support it
+///
+/// `expansion` overrides the default expansion factor, for the days that have one
+pub fn svg(input: Input, expansion: Option<usize>) -> anyhow::Result<svg::Svg> {
+    match input.day {
+        11 => day11::svg(&input.input, expansion.unwrap_or(2)),
+        _ => anyhow::bail!("Day {} does not support SVG output", input.day),
+    }
+}
+
 /// Helper to read a given input file into a string
 pub fn input(fname: &str) -> anyhow::Result<String> {
     let path = format!("inputs/{fname}");
@@ -1,21 +1,149 @@
 //! Helper math functions
 
+use anyhow::{bail, Result};
+use std::ops::RangeInclusive;
+
+pub mod poly;
+
 /// Finds the roots of a quadratic formula
 ///
 /// Roots = (-b +- sqrt(b^2 - 4ac)) / 2a
-pub fn quadratic(a: f64, b: f64, c: f64) -> (f64, f64) {
-    assert!(a != 0.0, "No valid solutions for quadratic equation");
+///
+/// This is floating point, so roots that should be whole numbers may land slightly to either side.
+/// Prefer [`product_above`] for exact integer answers.
+pub fn quadratic(a: f64, b: f64, c: f64) -> Result<(f64, f64)> {
+    if a == 0.0 {
+        bail!("Not a quadratic equation: a = 0");
+    }
     let delta = b * b - 4.0 * a * c;
-    assert!(
-        delta >= 0.0,
-        "No real solutions exist for quadratic equation"
-    );
+    if delta < 0.0 {
+        bail!("No real solutions exist for quadratic equation");
+    }
     let root1 = (-b - delta.sqrt()) / (2.0 * a);
     let root2 = (-b + delta.sqrt()) / (2.0 * a);
-    (root1, root2)
+    Ok((root1, root2))
+}
+
+/// Integer square root: the largest `r` with `r * r <= n`
+pub fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    // Newton's method started above the root decreases monotonically onto floor(sqrt(n))
+    let mut x = 1 << (128 - n.leading_zeros()).div_ceil(2);
+    loop {
+        let y = (x + n / x) / 2;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+/// Every integer `x` in `0..=sum` where `x * (sum - x) > threshold`, or None if there aren't any
+///
+/// The product is symmetric around `sum / 2`, so the solutions are `lo..=sum - lo` for the smallest
+/// solution `lo`. That's found from the real root `(sum - sqrt(sum^2 - 4 * threshold)) / 2`
+/// using an integer square root, then corrected by at most a step or two of exact checks. All the
+/// arithmetic is done in `u128`, so nothing is lost to rounding even for huge inputs.
+pub fn product_above(sum: u64, threshold: u64) -> Option<RangeInclusive<u64>> {
+    let (sum, threshold) = (sum as u128, threshold as u128);
+    let exceeds = |x: u128| x * (sum - x) > threshold;
+
+    let discriminant = (sum * sum).checked_sub(4 * threshold)?;
+    let mut lo = (sum - isqrt(discriminant)) / 2;
+    while lo <= sum / 2 && !exceeds(lo) {
+        lo += 1;
+    }
+    if lo > sum / 2 {
+        return None;
+    }
+    Some(lo as u64..=(sum - lo) as u64)
 }
 
 pub type Coord = (usize, usize);
 pub fn manhattan_distance(a: Coord, b: Coord) -> usize {
     a.0.abs_diff(b.0) + a.1.abs_diff(b.1)
 }
+
+/// Sum of the Manhattan distances between every pair of points
+///
+/// The distance splits into independent x and y parts, and along one sorted axis the `i`th value
+/// is `i` times further than each value before it, less their prefix sum. So this is O(n log n)
+/// rather than visiting every pair.
+pub fn pairwise_manhattan_sum(points: &[Coord]) -> usize {
+    let axis_sum = |mut values: Vec<usize>| {
+        values.sort_unstable();
+        let mut prefix = 0;
+        let mut sum = 0;
+        for (i, v) in values.into_iter().enumerate() {
+            sum += i * v - prefix;
+            prefix += v;
+        }
+        sum
+    };
+    axis_sum(points.iter().map(|p| p.0).collect()) + axis_sum(points.iter().map(|p| p.1).collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_quadratic() {
+        assert_eq!(quadratic(1.0, -3.0, 2.0).unwrap(), (1.0, 2.0));
+        assert!(quadratic(1.0, 0.0, 1.0).is_err());
+        assert!(quadratic(0.0, 1.0, 1.0).is_err());
+    }
+
+    #[test]
+    fn test_pairwise_manhattan_sum() {
+        let points = [(0, 0), (3, 1), (1, 4), (3, 1), (7, 2)];
+        let brute_force: usize = points
+            .iter()
+            .enumerate()
+            .flat_map(|(i, a)| points[i + 1..].iter().map(|b| manhattan_distance(*a, *b)))
+            .sum();
+        assert_eq!(pairwise_manhattan_sum(&points), brute_force);
+        assert_eq!(pairwise_manhattan_sum(&[(5, 5)]), 0);
+        assert_eq!(pairwise_manhattan_sum(&[]), 0);
+    }
+
+    #[test]
+    fn test_isqrt() {
+        for n in 0..10_000_u128 {
+            let r = isqrt(n);
+            assert!(r * r <= n && (r + 1) * (r + 1) > n, "isqrt({n}) = {r}");
+        }
+        assert_eq!(isqrt(u128::MAX), u64::MAX as u128);
+        let big = (u64::MAX as u128 - 1) * (u64::MAX as u128 - 1);
+        assert_eq!(isqrt(big), u64::MAX as u128 - 1);
+        assert_eq!(isqrt(big - 1), u64::MAX as u128 - 2);
+    }
+
+    #[test]
+    fn test_product_above() {
+        assert_eq!(product_above(7, 9), Some(2..=5));
+        // Roots at exactly 10 and 20 must be excluded since ties don't count
+        assert_eq!(product_above(30, 200), Some(11..=19));
+        assert_eq!(product_above(3, 2), None);
+        assert_eq!(product_above(4, 4), None);
+        assert_eq!(product_above(4, 3), Some(2..=2));
+        assert_eq!(product_above(0, 0), None);
+        assert_eq!(product_above(10, 0), Some(1..=9));
+    }
+
+    #[test]
+    fn test_product_above_large() {
+        // Near u64::MAX, f64 can't even represent the sum exactly
+        let sum = u64::MAX;
+        assert_eq!(product_above(sum, sum - 1), Some(2..=sum - 2));
+        assert_eq!(product_above(sum, 0), Some(1..=sum - 1));
+        // Whole-number roots at 2^31 and 3 * 2^31, tying the record
+        let threshold = (1 << 31) * (3 << 31);
+        assert_eq!(
+            product_above(1 << 33, threshold),
+            Some((1 << 31) + 1..=(3 << 31) - 1)
+        );
+    }
+}
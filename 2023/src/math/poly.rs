@@ -0,0 +1,225 @@
+//! Polynomials through sampled points, for puzzles that ask to continue a sequence
+//!
+//! A sequence whose repeated differences eventually become all zeros is a polynomial, so it can be
+//! evaluated at any index, including far past the samples. All arithmetic is exact: integers are
+//! unbounded and interpolating between arbitrary points uses rationals.
+
+use crate::prelude::*;
+use num::{BigInt, BigRational, ToPrimitive, Zero};
+
+/// Finite-difference table for `values`
+///
+/// The first row is `values` itself and each row after holds the differences between neighbors
+/// in the row above. The table stops at the first row of all zeros, or when a row has no
+/// neighbors left to subtract.
+pub fn differences(values: &[i64]) -> Vec<Vec<BigInt>> {
+    let mut table = vec![values.iter().map(|&v| BigInt::from(v)).collect_vec()];
+    loop {
+        let row = table.last().unwrap();
+        if row.len() < 2 || row.iter().all(Zero::is_zero) {
+            return table;
+        }
+        let next = row.iter().tuple_windows().map(|(a, b)| b - a).collect();
+        table.push(next);
+    }
+}
+
+/// A polynomial in Newton form: `c0 + c1 (x - x0) + c2 (x - x0)(x - x1) + ...`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polynomial {
+    nodes: Vec<BigRational>,
+    coefficients: Vec<BigRational>,
+}
+
+impl Polynomial {
+    /// The lowest degree polynomial through every point, using Newton's divided differences
+    pub fn interpolate(points: &[(i64, i64)]) -> Result<Polynomial> {
+        let nodes = points.iter().map(|&(x, _)| rational(x)).collect_vec();
+        if !points.iter().map(|(x, _)| x).all_unique() {
+            bail!("Cannot interpolate through two points with the same x");
+        }
+
+        // Each pass replaces the tail with the next order of divided differences,
+        // leaving the Newton coefficients along the front
+        let mut coefficients = points.iter().map(|&(_, y)| rational(y)).collect_vec();
+        for order in 1..points.len() {
+            for i in (order..points.len()).rev() {
+                coefficients[i] =
+                    (&coefficients[i] - &coefficients[i - 1]) / (&nodes[i] - &nodes[i - order]);
+            }
+        }
+        Ok(Polynomial {
+            nodes,
+            coefficients,
+        })
+    }
+
+    /// The lowest degree polynomial with `p(i) = values[i]`
+    ///
+    /// Samples are evenly spaced, so the coefficients come straight from the difference table:
+    /// `c_k = Δ^k p(0) / k!`. Higher order terms that are zero are dropped.
+    pub fn from_sequence(values: &[i64]) -> Polynomial {
+        let mut factorial = BigInt::from(1);
+        let mut coefficients = Vec::new();
+        for (k, row) in differences(values).iter().enumerate() {
+            if row.iter().all(Zero::is_zero) {
+                break;
+            }
+            if k > 0 {
+                factorial *= k;
+            }
+            coefficients.push(BigRational::new(row[0].clone(), factorial.clone()));
+        }
+        let nodes = (0..coefficients.len() as i64).map(rational).collect();
+        Polynomial {
+            nodes,
+            coefficients,
+        }
+    }
+
+    /// Degree of the polynomial, treating the zero polynomial as degree 0
+    pub fn degree(&self) -> usize {
+        self.coefficients
+            .iter()
+            .rposition(|c| !c.is_zero())
+            .unwrap_or(0)
+    }
+
+    /// Evaluates the polynomial at `x`
+    pub fn eval(&self, x: i64) -> BigRational {
+        let x = rational(x);
+        // Horner's method, nested around the Newton nodes
+        self.coefficients
+            .iter()
+            .zip(&self.nodes)
+            .rev()
+            .fold(BigRational::zero(), |acc, (c, node)| acc * (&x - node) + c)
+    }
+
+    /// Evaluates the polynomial at `x`, failing unless the result is an integer that fits in an i64
+    pub fn eval_integer(&self, x: i64) -> Result<i64> {
+        integer(self.eval(x))
+    }
+}
+
+/// Evaluates the Lagrange form of the polynomial through `points` at `x`, without building it
+pub fn lagrange(points: &[(i64, i64)], x: i64) -> Result<BigRational> {
+    let x = rational(x);
+    let mut sum = BigRational::zero();
+    for (i, &(xi, yi)) in points.iter().enumerate() {
+        let mut term = rational(yi);
+        for (j, &(xj, _)) in points.iter().enumerate() {
+            if i == j {
+                continue;
+            }
+            if xi == xj {
+                bail!("Cannot interpolate through two points with x = {xi}");
+            }
+            term *= (&x - rational(xj)) / rational(xi - xj);
+        }
+        sum += term;
+    }
+    Ok(sum)
+}
+
+/// Value of the sequence at `index`, continuing it as a polynomial
+///
+/// `values` are at indices `0..values.len()`, so `values.len()` is the next item and `-1` the
+/// one before the first.
+pub fn extrapolate(values: &[i64], index: i64) -> Result<i64> {
+    Polynomial::from_sequence(values).eval_integer(index)
+}
+
+fn rational(n: i64) -> BigRational {
+    BigRational::from_integer(n.into())
+}
+
+fn integer(value: BigRational) -> Result<i64> {
+    if !value.is_integer() {
+        bail!("Expected an integer but found {value}");
+    }
+    value
+        .to_integer()
+        .to_i64()
+        .with_context(|| format!("{value} does not fit in an i64"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn quadratic(n: i64) -> i64 {
+        3 * n * n - 5 * n + 7
+    }
+
+    #[test]
+    fn test_differences() {
+        let table = differences(&[1, 3, 6, 10, 15]);
+        let table = table
+            .iter()
+            .map(|row| row.iter().map(|n| n.to_i64().unwrap()).collect_vec())
+            .collect_vec();
+        assert_eq!(
+            table,
+            vec![
+                vec![1, 3, 6, 10, 15],
+                vec![2, 3, 4, 5],
+                vec![1, 1, 1],
+                vec![0, 0]
+            ]
+        );
+        assert_eq!(differences(&[4]).len(), 1);
+    }
+
+    #[test]
+    fn test_from_sequence() {
+        let values = (0..6).map(quadratic).collect_vec();
+        let poly = Polynomial::from_sequence(&values);
+        assert_eq!(poly.degree(), 2);
+        for n in -10..20 {
+            assert_eq!(poly.eval_integer(n).unwrap(), quadratic(n));
+        }
+        assert_eq!(Polynomial::from_sequence(&[0, 0, 0]).degree(), 0);
+        assert_eq!(extrapolate(&[], 5).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_far_future() {
+        let values = (0..3).map(quadratic).collect_vec();
+        assert_eq!(extrapolate(&values, 26501365).unwrap(), quadratic(26501365));
+        // A steep enough sequence overflows long before the index does
+        assert!(extrapolate(&[1, 1 << 40, 1 << 41], 1 << 40).is_err());
+    }
+
+    #[test]
+    fn test_interpolate() {
+        // Unevenly spaced samples, like measuring every 131 steps from an offset of 65
+        let points = [65, 196, 327, 1000].map(|x| (x, quadratic(x)));
+        let poly = Polynomial::interpolate(&points).unwrap();
+        assert_eq!(poly.degree(), 2);
+        assert_eq!(poly.eval_integer(26501365).unwrap(), quadratic(26501365));
+        assert_eq!(
+            lagrange(&points, 26501365).unwrap(),
+            rational(quadratic(26501365))
+        );
+    }
+
+    #[test]
+    fn test_rational_results() {
+        let points = [(0, 0), (2, 1)];
+        let half = BigRational::new(1.into(), 2.into());
+        assert_eq!(Polynomial::interpolate(&points).unwrap().eval(1), half);
+        assert_eq!(lagrange(&points, 1).unwrap(), half);
+        assert!(Polynomial::interpolate(&points)
+            .unwrap()
+            .eval_integer(1)
+            .is_err());
+    }
+
+    #[test]
+    fn test_duplicate_x() {
+        let points = [(1, 2), (1, 3)];
+        assert!(Polynomial::interpolate(&points).is_err());
+        assert!(lagrange(&points, 0).is_err());
+    }
+}
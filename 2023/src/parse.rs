@@ -43,18 +43,21 @@ where
 }
 
 use grid::Grid;
+/// Parses a block of characters into a grid indexed by `(x, y)`
+///
+/// Since the grid is indexed `(x, y)`, its `rows()` are the width and `cols()` the height.
 pub fn parse_2d<T>(input: &str) -> anyhow::Result<Grid<T>>
 where
     T: TryFrom<char>,
     <T as TryFrom<char>>::Error: std::error::Error + Send + Sync + 'static,
 {
-    let width = input.trim().lines().next().unwrap().len();
+    let height = input.trim().lines().count();
     let items: Vec<T> = input
         .chars()
         .filter(|c| !c.is_whitespace())
         .map(T::try_from)
         .try_collect()?;
-    let arr2 = Grid::from_vec_with_order(items, width, grid::Order::ColumnMajor);
+    let arr2 = Grid::from_vec_with_order(items, height, grid::Order::ColumnMajor);
     Ok(arr2)
 }
 
@@ -77,3 +80,24 @@ where
         .map(F::from_str)
         .collect()
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_2d_non_square() {
+        let grid = parse_2d::<char>("abc\ndef\n").unwrap();
+        // Indexed (x, y): rows() is the width and cols() the height
+        assert_eq!(grid.size(), (3, 2));
+        assert_eq!(grid[(0, 0)], 'a');
+        assert_eq!(grid[(2, 0)], 'c');
+        assert_eq!(grid[(0, 1)], 'd');
+        assert_eq!(grid[(2, 1)], 'f');
+
+        let tall = parse_2d::<char>("ab\ncd\nef\n").unwrap();
+        assert_eq!(tall.size(), (2, 3));
+        assert_eq!(tall[(1, 0)], 'b');
+        assert_eq!(tall[(0, 2)], 'e');
+    }
+}
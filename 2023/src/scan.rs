@@ -0,0 +1,133 @@
+//! Scanning blocks of text as a grid of characters
+//!
+//! Cells are addressed by `(row, col)`, where the column is a byte offset into the line, so the
+//! input is expected to be ASCII. Each line is used as-is, so the grid may be ragged and lines may
+//! end in either `\n` or `\r\n`.
+
+use std::ops::Range;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scanner<'a> {
+    lines: Vec<&'a str>,
+}
+
+/// A horizontal run of characters on a single row
+#[derive(Debug, Clone, PartialEq)]
+pub struct Span<'a> {
+    pub row: usize,
+    pub cols: Range<usize>,
+    pub text: &'a str,
+}
+
+impl<'a> Scanner<'a> {
+    pub fn new(input: &'a str) -> Scanner<'a> {
+        Scanner {
+            lines: input.lines().collect(),
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Character at `(row, col)`, or None if it's past the end of the grid or of its line
+    pub fn get(&self, row: usize, col: usize) -> Option<char> {
+        let byte = self.lines.get(row)?.as_bytes().get(col)?;
+        Some(*byte as char)
+    }
+
+    /// Every maximal horizontal run of characters matching `pred`, top to bottom and left to right
+    pub fn spans<F>(&self, pred: F) -> impl Iterator<Item = Span<'a>> + '_
+    where
+        F: Fn(char) -> bool + Copy + 'a,
+    {
+        self.lines.iter().enumerate().flat_map(move |(row, line)| {
+            let bytes = line.as_bytes();
+            let mut col = 0;
+            std::iter::from_fn(move || {
+                while col < bytes.len() && !pred(bytes[col] as char) {
+                    col += 1;
+                }
+                let start = col;
+                while col < bytes.len() && pred(bytes[col] as char) {
+                    col += 1;
+                }
+                (start < col).then(|| Span {
+                    row,
+                    cols: start..col,
+                    text: &line[start..col],
+                })
+            })
+        })
+    }
+
+    /// The cells bordering the columns `cols` of `row`, including diagonals, as `((row, col), char)`
+    ///
+    /// Cells that fall outside the grid, or past the end of a short line, are skipped.
+    pub fn neighbors(
+        &self,
+        row: usize,
+        cols: Range<usize>,
+    ) -> impl Iterator<Item = ((usize, usize), char)> + '_ {
+        let rows = row.saturating_sub(1)..=row + 1;
+        let border = cols.start.saturating_sub(1)..=cols.end;
+        rows.flat_map(move |r| border.clone().map(move |c| (r, c)))
+            .filter(move |&(r, c)| r != row || !cols.contains(&c))
+            .filter_map(|(r, c)| Some(((r, c), self.get(r, c)?)))
+    }
+
+    /// The cells bordering a span, see [`Scanner::neighbors`]
+    pub fn around(&self, span: &Span) -> impl Iterator<Item = ((usize, usize), char)> + '_ {
+        self.neighbors(span.row, span.cols.clone())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use itertools::Itertools;
+
+    #[test]
+    fn test_spans() {
+        let scanner = Scanner::new("12..3\r\n.\r\n45.67");
+        let spans = scanner.spans(|c| c.is_ascii_digit()).collect_vec();
+        let found = spans
+            .iter()
+            .map(|s| (s.row, s.cols.clone(), s.text))
+            .collect_vec();
+        assert_eq!(
+            found,
+            vec![
+                (0, 0..2, "12"),
+                (0, 4..5, "3"),
+                (2, 0..2, "45"),
+                (2, 3..5, "67"),
+            ]
+        );
+        assert_eq!(scanner.rows(), 3);
+        assert_eq!(scanner.get(0, 4), Some('3'));
+        assert_eq!(scanner.get(1, 1), None);
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let scanner = Scanner::new("abcd\nefgh\nijkl");
+        let around = |row, cols| {
+            scanner
+                .neighbors(row, cols)
+                .map(|(_, c)| c)
+                .collect::<String>()
+        };
+        assert_eq!(around(1, 1..3), "abcdehijkl");
+        assert_eq!(around(0, 0..1), "bef");
+        assert_eq!(around(2, 3..4), "ghk");
+    }
+
+    #[test]
+    fn test_ragged_neighbors() {
+        let scanner = Scanner::new("a\nbcd\ne");
+        let span = scanner.spans(|c| c == 'd').next().unwrap();
+        let cells = scanner.around(&span).collect_vec();
+        assert_eq!(cells, vec![((1, 1), 'c')]);
+    }
+}
@@ -1,12 +1,40 @@
 use anyhow::{bail, format_err, Context, Result};
 use std::fs;
+use std::time::Duration;
 
 fn main() -> Result<()> {
     let mut args = ::std::env::args().skip(1);
     if args.len() < 1 {
-        bail!("USAGE: aoc DAY[-PART] [FILE]");
+        bail!("USAGE: aoc DAY[-PART] [FILE] [--animate | --render OUT.gif|OUT.png] [--delay MS]");
     }
 
+    // Separate flags from the positional puzzle and file arguments
+    let mut animate = false;
+    let mut animate_opts = aoc::animate::Options::default();
+    let mut render_path = None;
+    let mut positional = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--animate" => animate = true,
+            "--render" => {
+                let path = args
+                    .next()
+                    .ok_or_else(|| format_err!("--render requires an output file"))?;
+                render_path = Some(path);
+            }
+            "--delay" => {
+                let ms = args
+                    .next()
+                    .ok_or_else(|| format_err!("--delay requires a number of milliseconds"))?
+                    .parse()
+                    .context("Failed to parse --delay milliseconds")?;
+                animate_opts.delay = Duration::from_millis(ms);
+            }
+            _ => positional.push(arg),
+        }
+    }
+    let mut args = positional.into_iter();
+
     let puzzle = args
         .next()
         .ok_or_else(|| format_err!("Must specifiy a puzzle (e.g. 2-1 for day 2 part 1)"))?;
@@ -25,7 +53,17 @@ fn main() -> Result<()> {
 
     let file_path = args.next().unwrap_or_else(|| format!("inputs/day{}", day));
     let input = fs::read_to_string(file_path).context("Unable to open input file")?;
-    let output = aoc::apply(aoc::Input { day, part, input })?;
+    let input = aoc::Input { day, part, input };
+
+    if animate {
+        return aoc::animate(input, &animate_opts);
+    }
+    if let Some(path) = render_path {
+        let frames = aoc::render(input)?;
+        return aoc::render::save(path, &frames, animate_opts.delay);
+    }
+
+    let output = aoc::apply(input)?;
 
     println!("{}", output);
     Ok(())
@@ -1,13 +1,21 @@
 //! [Advent of Code Day 4](https://adventofcode.com/2024/day/4)
 //!
-//! A bit of an ugly solution.
-//! - Part 1 is generic word search solution
-//! - Part 2 is hard-coded to the unusual X-shaped 'MAS' intersection solution
+//! Both parts are searches of the letter grid:
+//! - Part 1 reads a word in all eight directions
+//! - Part 2 matches an X-shaped stencil of two crossing 'MAS' in every rotation and reflection
 
 use crate::parse;
 use crate::prelude::*;
+use crate::search::{self, Stencil};
 use grid::Grid;
 
+/// Two diagonal 'MAS' crossing on their 'A'
+const X_MAS: &str = "
+    M.S
+    .A.
+    M.S
+";
+
 pub fn part1(input: &str) -> Result<u32> {
     let ws = WordSearch::from_str(input)?;
     let count = ws.count_occurrences("XMAS");
@@ -16,7 +24,7 @@ pub fn part1(input: &str) -> Result<u32> {
 
 pub fn part2(input: &str) -> Result<u32> {
     let ws = WordSearch::from_str(input)?;
-    let count = ws.count_x_mas();
+    let count = ws.count_stencil(&X_MAS.parse()?);
     Ok(count as u32)
 }
 
@@ -26,152 +34,12 @@ struct WordSearch {
 }
 
 impl WordSearch {
-    fn count_x_mas(&self) -> usize {
-        let w = self.grid.cols();
-        let h = self.grid.rows();
-        let mut count = 0;
-
-        for row in 1..h - 1 {
-            for col in 1..w - 1 {
-                if self.check_x_mas((row, col)) {
-                    count += 1;
-                }
-            }
-        }
-
-        count
-    }
-
     fn count_occurrences(&self, word: &str) -> usize {
-        let word_rev: String = word.chars().rev().collect();
-        let w = self.grid.cols();
-        let h = self.grid.rows();
-        let mut count = 0;
-
-        // Forward
-        for row in 0..h {
-            for col in 0..w {
-                // FORWARD / BACKWARD
-                if col + word.len() <= w {
-                    if self.check_forward(word, (row, col)) {
-                        // eprintln!("FORWARD at ({row},{col})");
-                        count += 1;
-                    }
-                    if self.check_forward(&word_rev, (row, col)) {
-                        // eprintln!("BACKWARD to ({row},{col})");
-                        count += 1;
-                    }
-                }
-
-                // UP / DOWN
-                if row + word.len() <= h {
-                    if self.check_down(word, (row, col)) {
-                        // eprintln!("DOWN at ({row},{col})");
-                        count += 1;
-                    }
-                    if self.check_down(&word_rev, (row, col)) {
-                        // eprintln!("UP to ({row},{col})");
-                        count += 1;
-                    }
-                }
-
-                // DIAGONAL
-                if col + word.len() <= w && row + word.len() <= h {
-                    if self.check_down_right(word, (row, col)) {
-                        // eprintln!("DOWN RIGHT at ({row},{col})");
-                        count += 1;
-                    }
-                    if self.check_down_right(&word_rev, (row, col)) {
-                        // eprintln!("UP LEFT to ({row},{col})");
-                        count += 1;
-                    }
-                }
-
-                if col + word.len() <= w && row + 1 >= word.len() {
-                    if self.check_up_right(word, (row, col)) {
-                        // eprintln!("UP RIGHT at ({row},{col})");
-                        count += 1;
-                    }
-                    if self.check_up_right(&word_rev, (row, col)) {
-                        // eprintln!("DOWN LEFT to ({row},{col})");
-                        count += 1;
-                    }
-                }
-            }
-        }
-
-        count
-    }
-
-    // Checks for MAS diagonals intersecting on the A at the specified coordinate
-    fn check_x_mas(&self, (row, col): (usize, usize)) -> bool {
-        assert!(row > 0);
-        assert!(col > 0);
-        assert!(col + 1 < self.grid.cols());
-        assert!(row + 1 < self.grid.rows());
-
-        if self.grid[(row, col)] != 'A' {
-            return false;
-        }
-
-        let nw = self.grid[(row - 1, col - 1)];
-        let ne = self.grid[(row - 1, col + 1)];
-        let sw = self.grid[(row + 1, col - 1)];
-        let se = self.grid[(row + 1, col + 1)];
-
-        matches!(
-            (nw, se, ne, sw),
-            ('M', 'S', 'M', 'S')
-                | ('M', 'S', 'S', 'M')
-                | ('S', 'M', 'M', 'S')
-                | ('S', 'M', 'S', 'M')
-        )
-    }
-
-    fn check_forward(&self, word: &str, (row, col): (usize, usize)) -> bool {
-        assert!(col + word.len() <= self.grid.cols());
-
-        for (idx, c) in word.chars().enumerate() {
-            if self.grid[(row, col + idx)] != c {
-                return false;
-            }
-        }
-        true
-    }
-
-    fn check_down(&self, word: &str, (row, col): (usize, usize)) -> bool {
-        assert!(row + word.len() <= self.grid.rows());
-
-        for (idx, c) in word.chars().enumerate() {
-            if self.grid[(row + idx, col)] != c {
-                return false;
-            }
-        }
-        true
+        search::find_word(&self.grid, word).len()
     }
 
-    fn check_down_right(&self, word: &str, (row, col): (usize, usize)) -> bool {
-        assert!(col + word.len() <= self.grid.cols());
-        assert!(row + word.len() <= self.grid.rows());
-
-        for (idx, c) in word.chars().enumerate() {
-            if self.grid[(row + idx, col + idx)] != c {
-                return false;
-            }
-        }
-        true
-    }
-
-    fn check_up_right(&self, word: &str, (row, col): (usize, usize)) -> bool {
-        assert!(col + word.len() <= self.grid.cols());
-        assert!(row + 1 >= word.len());
-
-        for (idx, c) in word.chars().enumerate() {
-            if self.grid[(row - idx, col + idx)] != c {
-                return false;
-            }
-        }
-        true
+    fn count_stencil(&self, stencil: &Stencil) -> usize {
+        search::find_stencil(&self.grid, stencil).len()
     }
 }
 
@@ -222,13 +90,27 @@ mod test {
     }
 
     #[test]
-    fn test_check_word() {
+    fn test_find_word() {
         let ws = WordSearch::from_str(SAMPLE).unwrap();
-        assert!(ws.check_forward("MMMS", (0, 0)));
-        assert!(ws.check_forward("XMAS", (0, 5)));
-        assert!(ws.check_down("MMAM", (0, 0)));
-        assert!(ws.check_down_right("MSXM", (0, 0)));
-        assert!(ws.check_up_right("MMAS", (3, 0)));
+        let found = search::find_word(&ws.grid, "XMAS");
+        assert_eq!(found.len(), 18);
+        // Reading right along the top row, and up-left from the bottom right
+        assert!(found.contains(&search::WordMatch {
+            start: (0, 5),
+            dir: (0, 1)
+        }));
+        assert!(found.contains(&search::WordMatch {
+            start: (9, 9),
+            dir: (-1, -1)
+        }));
+    }
+
+    #[test]
+    fn test_x_mas_orientations() {
+        let stencil: Stencil = X_MAS.parse().unwrap();
+        assert_eq!(stencil.orientations().len(), 4);
+        let ws = WordSearch::from_str("S.S\n.A.\nM.M\n").unwrap();
+        assert_eq!(ws.count_stencil(&stencil), 1);
     }
 
     const CARDINAL: &str = indoc! {"
@@ -1,68 +1,116 @@
 //! [Advent of Code Day 5](https://adventofcode.com/2024/day/5)
 
 use crate::{parse, prelude::*};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
 
 pub fn part1(input: &str) -> Result<u32> {
-    let input = Input::from_str(input).unwrap();
+    let input = Input::from_str(input)?;
     let sum = input.well_ordered_middle_sum();
     Ok(sum)
 }
 
 pub fn part2(input: &str) -> Result<u32> {
-    let mut input = Input::from_str(input).unwrap();
-    let sum = input.reordered_middle_sum();
+    let input = Input::from_str(input)?;
+    let sum = input.reordered_middle_sum()?;
     Ok(sum)
 }
 
 #[derive(Debug, Clone)]
 struct Input {
-    rules: Vec<(u32, u32)>,
+    rules: Rules,
     updates: Vec<Vec<u32>>,
 }
 
 impl Input {
     fn well_ordered_middle_sum(&self) -> u32 {
+        self.updates
+            .iter()
+            .filter(|update| self.rules.well_ordered(update))
+            .map(|update| update[update.len() / 2])
+            .sum()
+    }
+
+    fn reordered_middle_sum(&self) -> Result<u32> {
         let mut sum = 0;
         for update in &self.updates {
-            if well_ordered(&self.rules, update) {
+            if !self.rules.well_ordered(update) {
+                let update = self.rules.reorder(update)?;
                 sum += update[update.len() / 2];
             }
         }
-        sum
+        Ok(sum)
     }
+}
 
-    fn reordered_middle_sum(&mut self) -> u32 {
-        let mut sum = 0;
-        for update in &mut self.updates {
-            if !well_ordered(&self.rules, update) {
-                reorder(&self.rules, update);
-                sum += update[update.len() / 2];
+/// Page ordering rules indexed by page, so checks only look at the rules that involve a given page
+#[derive(Debug, Clone, Default)]
+struct Rules {
+    /// Maps each page to the pages that must come after it
+    after: HashMap<u32, HashSet<u32>>,
+}
+
+impl Rules {
+    fn add(&mut self, before: u32, after: u32) {
+        self.after.entry(before).or_default().insert(after);
+    }
+
+    /// An update is well-ordered if no page comes after a page that the rules require it to precede
+    fn well_ordered(&self, update: &[u32]) -> bool {
+        let mut seen = HashSet::with_capacity(update.len());
+        for page in update {
+            if let Some(after) = self.after.get(page) {
+                if !after.is_disjoint(&seen) {
+                    return false;
+                }
             }
+            seen.insert(*page);
         }
-        sum
+        true
     }
-}
 
-fn well_ordered(rules: &[(u32, u32)], update: &[u32]) -> bool {
-    for (page_a, page_b) in update.iter().tuple_combinations() {
-        for rule in rules {
-            if *page_b == rule.0 && *page_a == rule.1 {
-                return false;
+    /// Sorts the pages of an update topologically, using only the rules between pages in the update
+    ///
+    /// When several pages are free to go next, the one that came first in the update wins, so pages
+    /// with no rules between them keep their relative order. Fails if the rules form a cycle.
+    fn reorder(&self, update: &[u32]) -> Result<Vec<u32>> {
+        let index: HashMap<u32, usize> = update.iter().enumerate().map(|(i, p)| (*p, i)).collect();
+
+        // Edges of the rule graph restricted to the update, by position in the update
+        let mut successors = vec![Vec::new(); update.len()];
+        let mut predecessors = vec![0; update.len()];
+        for (i, page) in update.iter().enumerate() {
+            for after in self.after.get(page).into_iter().flatten() {
+                if let Some(&j) = index.get(after) {
+                    successors[i].push(j);
+                    predecessors[j] += 1;
+                }
             }
         }
-    }
-    true
-}
 
-/// Tests every pair of elements according to the rules
-/// Swaps any pair of elements that break any rule
-fn reorder(rules: &[(u32, u32)], update: &mut [u32]) {
-    for (left, right) in (0..update.len()).tuple_combinations() {
-        for rule in rules {
-            if update[right] == rule.0 && update[left] == rule.1 {
-                update.swap(left, right)
+        let mut ready: BinaryHeap<Reverse<usize>> = (0..update.len())
+            .filter(|i| predecessors[*i] == 0)
+            .map(Reverse)
+            .collect();
+        let mut ordered = Vec::with_capacity(update.len());
+        while let Some(Reverse(i)) = ready.pop() {
+            ordered.push(update[i]);
+            for &j in &successors[i] {
+                predecessors[j] -= 1;
+                if predecessors[j] == 0 {
+                    ready.push(Reverse(j));
+                }
             }
         }
+
+        if ordered.len() < update.len() {
+            let cycle: Vec<u32> = (0..update.len())
+                .filter(|i| predecessors[*i] > 0)
+                .map(|i| update[i])
+                .collect();
+            bail!("Rules for update {update:?} form a cycle between pages {cycle:?}");
+        }
+        Ok(ordered)
     }
 }
 
@@ -72,7 +120,7 @@ impl FromStr for Input {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self> {
-        let mut orderings = Vec::new();
+        let mut rules = Rules::default();
         let mut lines = s.lines();
         for line in lines.by_ref() {
             if line.trim().is_empty() {
@@ -81,17 +129,18 @@ impl FromStr for Input {
             let caps = RE_ORD
                 .captures(line)
                 .ok_or_else(|| Error::msg("Expected ordering rule"))?;
-            orderings.push((caps[1].parse().unwrap(), caps[2].parse().unwrap()));
+            rules.add(caps[1].parse()?, caps[2].parse()?);
         }
 
         let mut updates = Vec::new();
-        for line in lines {
-            updates.push(parse::extract_nums::<u32>(line)?);
+        for line in lines.filter(|line| !line.trim().is_empty()) {
+            let update = parse::extract_nums::<u32>(line)?;
+            if update.is_empty() {
+                bail!("Update line has no pages: {line}");
+            }
+            updates.push(update);
         }
-        Ok(Input {
-            rules: orderings,
-            updates,
-        })
+        Ok(Input { rules, updates })
     }
 }
 
@@ -134,24 +183,31 @@ mod test {
     #[test]
     fn test_parse() {
         let input = Input::from_str(SAMPLE).unwrap();
-        assert_eq!((47, 53), input.rules[0]);
+        assert!(input.rules.after[&47].contains(&53));
+        assert!(!input.rules.after[&53].contains(&47));
         assert_eq!(vec![75, 47, 61, 53, 29], input.updates[0]);
     }
 
+    #[test]
+    fn test_parse_empty_update() {
+        assert!(Input::from_str("47|53\n\n75,47\n,\n").is_err());
+        assert!(part1("47|53\n\nnone\n").is_err());
+    }
+
     #[test]
     fn test_well_ordered() {
         let input = Input::from_str(SAMPLE).unwrap();
         let rules = input.rules;
 
         // well-ordered
-        assert!(well_ordered(&rules, &[75, 47, 61, 53, 29]));
-        assert!(well_ordered(&rules, &[97, 61, 53, 29, 13]));
-        assert!(well_ordered(&rules, &[75, 29, 13]));
+        assert!(rules.well_ordered(&[75, 47, 61, 53, 29]));
+        assert!(rules.well_ordered(&[97, 61, 53, 29, 13]));
+        assert!(rules.well_ordered(&[75, 29, 13]));
 
         // Not well-ordered
-        assert!(!well_ordered(&rules, &[75, 97, 47, 61, 53]));
-        assert!(!well_ordered(&rules, &[61, 13, 29]));
-        assert!(!well_ordered(&rules, &[97, 13, 75, 29, 47]));
+        assert!(!rules.well_ordered(&[75, 97, 47, 61, 53]));
+        assert!(!rules.well_ordered(&[61, 13, 29]));
+        assert!(!rules.well_ordered(&[97, 13, 75, 29, 47]));
     }
 
     #[test]
@@ -160,17 +216,27 @@ mod test {
         let rules = input.rules;
 
         // Not well-ordered
-        let update = &mut [75, 97, 47, 61, 53];
-        reorder(&rules, update);
+        let update = rules.reorder(&[75, 97, 47, 61, 53]).unwrap();
         assert_eq!(update, &[97, 75, 47, 61, 53]);
 
-        let update = &mut [61, 13, 29];
-        reorder(&rules, update);
+        let update = rules.reorder(&[61, 13, 29]).unwrap();
         assert_eq!(update, &[61, 29, 13]);
 
-        let update = &mut [97, 13, 75, 29, 47];
-        reorder(&rules, update);
+        let update = rules.reorder(&[97, 13, 75, 29, 47]).unwrap();
         assert_eq!(update, &[97, 75, 47, 29, 13]);
+
+        // Unrelated pages keep their order
+        assert_eq!(rules.reorder(&[5, 13, 4, 97]).unwrap(), &[5, 4, 97, 13]);
+    }
+
+    #[test]
+    fn test_reorder_cycle() {
+        // The full rule set has a cycle, but it only matters if an update contains all of it
+        let input = Input::from_str("1|2\n2|3\n3|1\n\n3,2\n1,2,3\n").unwrap();
+        assert_eq!(input.rules.reorder(&[3, 2]).unwrap(), &[2, 3]);
+        let err = input.rules.reorder(&[1, 2, 3]).unwrap_err();
+        assert!(err.to_string().contains("cycle"));
+        assert!(part2("1|2\n2|3\n3|1\n\n1,2,3\n").is_err());
     }
 
     #[test]
@@ -2,8 +2,10 @@
 
 use std::collections::HashSet;
 
+use crate::animate::{self, Color};
 use crate::parse;
 use crate::prelude::*;
+use crate::render::{self, Image};
 
 pub fn part1(input: &str) -> Result<usize> {
     let map = Map::from_str(input).unwrap();
@@ -17,6 +19,37 @@ pub fn part2(input: &str) -> Result<usize> {
     Ok(count)
 }
 
+/// Animates the guard's patrol in the terminal
+pub fn animate(input: &str, opts: &animate::Options) -> Result<()> {
+    let map = Map::from_str(input)?;
+    let mut patrol = Patrol::new(&map);
+    animate::run(&mut patrol, opts, &mut std::io::stdout())?;
+    Ok(())
+}
+
+/// Renders the guard's patrol as roughly `max_frames` snapshots of the path so far
+pub fn render(input: &str, max_frames: usize) -> Result<Vec<Image>> {
+    let map = Map::from_str(input)?;
+
+    let mut steps = 0;
+    let mut patrol = Patrol::new(&map);
+    while animate::Frame::advance(&mut patrol) {
+        steps += 1;
+    }
+    let stride = cmp::max(1, steps / max_frames.max(1));
+
+    let mut patrol = Patrol::new(&map);
+    let mut frames = vec![patrol.render()];
+    let mut step = 0;
+    while animate::Frame::advance(&mut patrol) {
+        step += 1;
+        if step % stride == 0 || step == steps {
+            frames.push(patrol.render());
+        }
+    }
+    Ok(frames)
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 enum Dir {
     N,
@@ -35,6 +68,15 @@ impl Dir {
         }
     }
 
+    fn glyph(&self) -> char {
+        match self {
+            Dir::N => '^',
+            Dir::E => '>',
+            Dir::S => 'v',
+            Dir::W => '<',
+        }
+    }
+
     fn deltas(&self) -> (i8, i8) {
         match self {
             Dir::N => (-1, 0),
@@ -177,6 +219,72 @@ impl Map {
     }
 }
 
+// Walks the guard's patrol one step per frame, remembering where they've been
+struct Patrol<'a> {
+    map: &'a Map,
+    guard: (usize, usize, Dir),
+    visited: HashSet<(usize, usize)>,
+}
+
+impl<'a> Patrol<'a> {
+    fn new(map: &'a Map) -> Patrol<'a> {
+        let guard = map.start();
+        let visited = HashSet::from([(guard.0, guard.1)]);
+        Patrol {
+            map,
+            guard,
+            visited,
+        }
+    }
+}
+
+impl Patrol<'_> {
+    fn render(&self) -> Image {
+        let (guard_row, guard_col, _) = self.guard;
+        Image::from_fn(self.map.rows(), self.map.cols(), 4, |row, col| {
+            if (row, col) == (guard_row, guard_col) {
+                render::RED
+            } else if self.map[(row, col)] == Pos::Obstruction {
+                render::BLUE
+            } else if self.visited.contains(&(row, col)) {
+                render::YELLOW
+            } else {
+                render::BLACK
+            }
+        })
+    }
+}
+
+impl animate::Frame for Patrol<'_> {
+    fn size(&self) -> (usize, usize) {
+        (self.map.rows(), self.map.cols())
+    }
+
+    fn cell(&self, row: usize, col: usize) -> (char, Color) {
+        let (guard_row, guard_col, dir) = self.guard;
+        if (row, col) == (guard_row, guard_col) {
+            return (dir.glyph(), Color::Red);
+        }
+        match self.map[(row, col)] {
+            Pos::Obstruction => ('#', Color::Blue),
+            _ if self.visited.contains(&(row, col)) => ('X', Color::Yellow),
+            _ => ('.', Color::Default),
+        }
+    }
+
+    fn advance(&mut self) -> bool {
+        let (row, col, dir) = self.guard;
+        match self.map.next_patrol(row, col, dir) {
+            Some(next) => {
+                self.guard = next;
+                self.visited.insert((next.0, next.1));
+                true
+            }
+            None => false,
+        }
+    }
+}
+
 impl std::ops::Deref for Map {
     type Target = grid::Grid<Pos>;
 
@@ -249,6 +357,35 @@ mod test {
         assert!(map.contains_loop());
     }
 
+    #[test]
+    fn test_patrol_frames() {
+        use animate::Frame;
+
+        let map = Map::from_str(SAMPLE).unwrap();
+        let mut patrol = Patrol::new(&map);
+        assert_eq!(patrol.cell(6, 4), ('^', Color::Red));
+        assert!(patrol.advance());
+        assert_eq!(patrol.cell(6, 4), ('X', Color::Yellow));
+        assert_eq!(patrol.cell(5, 4), ('^', Color::Red));
+        while patrol.advance() {}
+        assert_eq!(patrol.visited.len(), 41);
+    }
+
+    #[test]
+    fn test_render() {
+        let frames = render(SAMPLE, 100).unwrap();
+        // One frame for the start plus one per step of the patrol
+        assert_eq!(frames.len(), 45);
+        let first = &frames[0];
+        assert_eq!((first.width(), first.height()), (40, 40));
+        assert_eq!(first.pixel(6 * 4, 4 * 4), render::RED);
+        assert_eq!(first.pixel(0, 4 * 4), render::BLUE);
+        let last = frames.last().unwrap();
+        assert_eq!(last.pixel(6 * 4, 4 * 4), render::YELLOW);
+
+        assert_eq!(render(SAMPLE, 10).unwrap().len(), 12);
+    }
+
     #[test]
     fn test_part1() {
         assert_eq!(part1(SAMPLE).unwrap(), 41);
@@ -4,7 +4,7 @@ use crate::{parse, prelude::*};
 
 pub fn part1(input: &str) -> Result<u64> {
     let lines = parse::parse_lines_with(input, Line::from_str)?;
-    let ops = &[Operator::Add, Operator::Mult];
+    let ops = &[Op::Add, Op::Mult];
     let calibration = lines
         .iter()
         .filter(|l| l.has_solution(ops))
@@ -15,7 +15,7 @@ pub fn part1(input: &str) -> Result<u64> {
 
 pub fn part2(input: &str) -> Result<u64> {
     let lines = parse::parse_lines_with(input, Line::from_str)?;
-    let ops = &[Operator::Add, Operator::Mult, Operator::Concat];
+    let ops = &[Op::Add, Op::Mult, Op::Concat];
     let calibration = lines
         .iter()
         .filter(|l| l.has_solution(ops))
@@ -40,32 +40,151 @@ impl Line {
     }
 
     // Determines if the total can be achieved left-to-right with any of the specified operators
-    fn has_solution(&self, operators: &[Operator]) -> bool {
-        let mut partials = vec![self.operands[0]];
-        for n in &self.operands[1..] {
-            let mut next = Vec::new();
-            for p in partials.iter_mut() {
-                for op in operators {
-                    next.push(match op {
-                        Operator::Add => *p + *n,
-                        Operator::Mult => *p * *n,
-                        Operator::Concat => *p * 10_u64.pow(n.ilog10() + 1) + *n,
-                    })
-                }
+    fn has_solution<O: Operator + Copy>(&self, operators: &[O]) -> bool {
+        self.solve(operators).is_some()
+    }
+
+    /// Finds operators to place between the operands (evaluated left-to-right) that produce the total
+    ///
+    /// Works backwards from the total: the last operand must have been combined with whatever the
+    /// rest of the operands evaluate to, so each operator is undone to find what that must be. Most
+    /// operators can't be undone for most values (e.g. the total isn't divisible by the operand),
+    /// which prunes the search long before every combination is tried.
+    fn solve<O: Operator + Copy>(&self, operators: &[O]) -> Option<Vec<O>> {
+        let mut sequence = Vec::with_capacity(self.operands.len().saturating_sub(1));
+        if solve_rtl(self.total, &self.operands, operators, &mut sequence) {
+            Some(sequence)
+        } else {
+            None
+        }
+    }
+
+    /// Evaluates the operands left-to-right with the given operators, or None if any step fails
+    #[allow(unused)]
+    fn evaluate<O: Operator>(&self, operators: &[O]) -> Option<u64> {
+        let (first, rest) = self.operands.split_first()?;
+        if rest.len() != operators.len() {
+            return None;
+        }
+        rest.iter()
+            .zip(operators)
+            .try_fold(*first, |acc, (n, op)| op.apply(acc, *n))
+    }
+}
+
+// The innermost call resolves the first operator, so operators are pushed in order as the
+// recursion unwinds
+fn solve_rtl<O: Operator + Copy>(
+    target: u64,
+    operands: &[u64],
+    operators: &[O],
+    sequence: &mut Vec<O>,
+) -> bool {
+    let Some((last, rest)) = operands.split_last() else {
+        return false;
+    };
+    if rest.is_empty() {
+        return target == *last;
+    }
+    for op in operators {
+        let solved = match op.undo(target, *last) {
+            Undo::Impossible => false,
+            Undo::Lhs(prev) => solve_rtl(prev, rest, operators, sequence),
+            Undo::Any => evaluate_any(rest[0], &rest[1..], operators, sequence),
+        };
+        if solved {
+            sequence.push(*op);
+            return true;
+        }
+    }
+    false
+}
+
+// Searches forwards for any operators that evaluate the operands without failing, for when the
+// result doesn't constrain them
+fn evaluate_any<O: Operator + Copy>(
+    acc: u64,
+    operands: &[u64],
+    operators: &[O],
+    sequence: &mut Vec<O>,
+) -> bool {
+    let Some((next, rest)) = operands.split_first() else {
+        return true;
+    };
+    for op in operators {
+        if let Some(acc) = op.apply(acc, *next) {
+            sequence.push(*op);
+            if evaluate_any(acc, rest, operators, sequence) {
+                return true;
             }
-            partials = next;
+            sequence.pop();
         }
+    }
+    false
+}
+
+/// A binary operator that can be run in reverse
+pub trait Operator {
+    /// Combines two values, or None if the result isn't representable
+    fn apply(&self, lhs: u64, rhs: u64) -> Option<u64>;
+
+    /// The `lhs` for which `apply(lhs, rhs) == result`
+    fn undo(&self, result: u64, rhs: u64) -> Undo;
+}
 
-        partials.contains(&self.total)
+/// What the `lhs` of an operator must have been to produce a result
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Undo {
+    /// No `lhs` produces the result
+    Impossible,
+    /// Only this `lhs` produces the result
+    Lhs(u64),
+    /// Every `lhs` produces the result, e.g. multiplying by zero
+    Any,
+}
+
+impl From<Option<u64>> for Undo {
+    fn from(lhs: Option<u64>) -> Undo {
+        lhs.map_or(Undo::Impossible, Undo::Lhs)
     }
 }
 
-enum Operator {
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Op {
     Add,
     Mult,
     Concat,
 }
 
+impl Operator for Op {
+    fn apply(&self, lhs: u64, rhs: u64) -> Option<u64> {
+        match self {
+            Op::Add => lhs.checked_add(rhs),
+            Op::Mult => lhs.checked_mul(rhs),
+            Op::Concat => lhs.checked_mul(concat_shift(rhs))?.checked_add(rhs),
+        }
+    }
+
+    fn undo(&self, result: u64, rhs: u64) -> Undo {
+        match self {
+            Op::Add => result.checked_sub(rhs).into(),
+            Op::Mult if rhs == 0 && result == 0 => Undo::Any,
+            Op::Mult => (rhs != 0 && result.is_multiple_of(rhs))
+                .then(|| result / rhs)
+                .into(),
+            Op::Concat => {
+                let shift = concat_shift(rhs);
+                (result % shift == rhs).then(|| result / shift).into()
+            }
+        }
+    }
+}
+
+/// The power of ten that shifts a number left past all the digits of `n`
+fn concat_shift(n: u64) -> u64 {
+    10_u64.pow(n.checked_ilog10().unwrap_or(0) + 1)
+}
+
 impl FromStr for Line {
     type Err = Error;
 
@@ -103,7 +222,7 @@ mod test {
 
     #[test]
     fn test_ltr_solution() {
-        let ops = &[Operator::Add, Operator::Mult];
+        let ops = &[Op::Add, Op::Mult];
         assert!(Line::new(190, &[10, 19]).has_solution(ops));
         assert!(Line::new(3267, &[81, 40, 27]).has_solution(ops));
         assert!(!Line::new(83, &[17, 5]).has_solution(ops));
@@ -111,13 +230,79 @@ mod test {
         assert!(!Line::new(165, &[15, 6]).has_solution(ops));
         assert!(!Line::new(192, &[17, 8, 4]).has_solution(ops));
 
-        let ops = &[Operator::Add, Operator::Mult, Operator::Concat];
+        let ops = &[Op::Add, Op::Mult, Op::Concat];
         assert!(Line::new(156, &[15, 6]).has_solution(ops));
         assert!(Line::new(192, &[17, 8, 14]).has_solution(ops));
         assert!(Line::new(1755, &[17, 55]).has_solution(ops));
         assert!(Line::new(123456789, &[1, 234, 5678, 9]).has_solution(ops));
     }
 
+    #[test]
+    fn test_solve_sequence() {
+        let ops = &[Op::Add, Op::Mult, Op::Concat];
+        let line = Line::new(7290, &[6, 8, 6, 15]);
+        let sequence = line.solve(ops).unwrap();
+        assert_eq!(sequence, vec![Op::Mult, Op::Concat, Op::Mult]);
+        assert_eq!(line.evaluate(&sequence), Some(7290));
+
+        assert_eq!(Line::new(83, &[17, 5]).solve(ops), None);
+        assert_eq!(Line::new(5, &[5]).solve(ops), Some(vec![]));
+        assert_eq!(Line::new(10, &[10, 0]).solve(ops), Some(vec![Op::Add]));
+        assert_eq!(
+            Line::new(1434, &[12, 2, 34]).solve(ops),
+            Some(vec![Op::Add, Op::Concat])
+        );
+    }
+
+    #[test]
+    fn test_zero_operand() {
+        // Multiplying by zero loses the lhs, so the operands before it just need to evaluate
+        let ops = &[Op::Add, Op::Mult];
+        assert_eq!(Line::new(0, &[5, 0]).solve(ops), Some(vec![Op::Mult]));
+        let line = Line::new(7, &[3, 4, 0, 7]);
+        let sequence = line.solve(ops).unwrap();
+        assert_eq!(line.evaluate(&sequence), Some(7));
+        assert!(Line::new(0, &[0, 0]).has_solution(ops));
+        assert!(!Line::new(1, &[5, 0]).has_solution(ops));
+
+        let ops = &[Op::Add, Op::Mult, Op::Concat];
+        let line = Line::new(9, &[2, 3, 0, 9]);
+        let sequence = line.solve(ops).unwrap();
+        assert_eq!(line.evaluate(&sequence), Some(9));
+    }
+
+    #[test]
+    fn test_custom_operators() {
+        // Subtraction and integer division, undone by adding and multiplying back
+        #[derive(Debug, Copy, Clone, PartialEq)]
+        enum Inverse {
+            Sub,
+            Div,
+        }
+
+        impl Operator for Inverse {
+            fn apply(&self, lhs: u64, rhs: u64) -> Option<u64> {
+                match self {
+                    Inverse::Sub => lhs.checked_sub(rhs),
+                    Inverse::Div => (rhs != 0 && lhs.is_multiple_of(rhs)).then(|| lhs / rhs),
+                }
+            }
+
+            fn undo(&self, result: u64, rhs: u64) -> Undo {
+                match self {
+                    Inverse::Sub => result.checked_add(rhs).into(),
+                    Inverse::Div => result.checked_mul(rhs).into(),
+                }
+            }
+        }
+
+        let ops = &[Inverse::Sub, Inverse::Div];
+        let line = Line::new(3, &[100, 10, 2, 2]);
+        let sequence = line.solve(ops).unwrap();
+        assert_eq!(sequence, vec![Inverse::Div, Inverse::Div, Inverse::Sub]);
+        assert_eq!(line.evaluate(&sequence), Some(3));
+    }
+
     #[test]
     fn test_part1() {
         assert_eq!(part1(SAMPLE).unwrap(), 3749);
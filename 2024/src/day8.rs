@@ -5,6 +5,7 @@ use std::collections::{HashMap, HashSet};
 use maplit::hashset;
 
 use crate::prelude::*;
+use crate::render::{self, Image};
 
 pub fn part1(input: &str) -> Result<usize> {
     let map = Map::from_str(input)?;
@@ -18,6 +19,23 @@ pub fn part2(input: &str) -> Result<usize> {
     Ok(count)
 }
 
+/// Renders antennas colored by frequency, with their antinodes in yellow
+pub fn render(input: &str, include_harmonics: bool) -> Result<Vec<Image>> {
+    let map = Map::from_str(input)?;
+    let antinodes = map.antinodes(include_harmonics);
+    let antennas: HashMap<Coord, char> = map.antennas.iter().map(|a| (a.coord, a.freq)).collect();
+    let image = Image::from_fn(map.rows, map.cols, 4, |row, col| {
+        if let Some(freq) = antennas.get(&(row, col)) {
+            render::hashed_color(freq)
+        } else if antinodes.contains(&(row, col)) {
+            render::YELLOW
+        } else {
+            render::BLACK
+        }
+    });
+    Ok(vec![image])
+}
+
 type Coord = (usize, usize);
 
 #[derive(Debug, Clone)]
@@ -35,6 +53,10 @@ struct Map {
 
 impl Map {
     fn count_antinodes(&self, include_harmonics: bool) -> usize {
+        self.antinodes(include_harmonics).len()
+    }
+
+    fn antinodes(&self, include_harmonics: bool) -> HashSet<Coord> {
         // Group all the antennas by frequency
         let mut antenna_map: HashMap<char, HashSet<Coord>> = HashMap::new();
         for a in &self.antennas {
@@ -51,7 +73,7 @@ impl Map {
             let antinode_coords = calc_antinodes(self.rows, self.cols, &coords, include_harmonics);
             antinodes = antinodes.union(&antinode_coords).cloned().collect();
         }
-        antinodes.len()
+        antinodes
     }
 }
 
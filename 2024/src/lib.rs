@@ -1,8 +1,11 @@
 use anyhow::Context;
 use serde_json::{json, Value};
 
+pub mod animate;
 pub mod math;
 pub mod parse;
+pub mod render;
+pub mod search;
 
 #[allow(unused)]
 pub(crate) mod prelude {
@@ -68,6 +71,23 @@ macro_rules! bench_day {
 // Simply specify the days that are implemented
 handle_days![1, 2, 3, 4, 5, 6, 7, 8, 9, 11];
 
+/// Animates a puzzle's simulation in the terminal, for the days that support it
+pub fn animate(input: Input, opts: &animate::Options) -> anyhow::Result<()> {
+    match input.day {
+        6 => day6::animate(&input.input, opts),
+        _ => anyhow::bail!("Day {} does not support animation", input.day),
+    }
+}
+
+/// Renders a puzzle's grid state as image frames, for the days that support it
+pub fn render(input: Input) -> anyhow::Result<Vec<render::Image>> {
+    match input.day {
+        6 => day6::render(&input.input, 200),
+        8 => day8::render(&input.input, input.part == 2),
+        _ => anyhow::bail!("Day {} does not support rendering", input.day),
+    }
+}
+
 /// Helper to read a given input file into a string
 pub fn input(fname: &str) -> anyhow::Result<String> {
     let path = format!("inputs/{fname}");
@@ -0,0 +1,262 @@
+//! Image output for grid states: PNG frames and animated GIFs
+
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+use std::fs::File;
+use std::hash::{Hash, Hasher};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// An RGB color
+pub type Rgb = [u8; 3];
+
+pub const BLACK: Rgb = [0, 0, 0];
+pub const WHITE: Rgb = [255, 255, 255];
+pub const GRAY: Rgb = [96, 96, 96];
+pub const RED: Rgb = [220, 50, 47];
+pub const GREEN: Rgb = [133, 153, 0];
+pub const BLUE: Rgb = [38, 139, 210];
+pub const YELLOW: Rgb = [181, 137, 0];
+
+/// Picks a stable, reasonably bright color for any hashable value (e.g. a cell label)
+///
+/// This hashes with FNV-1a instead of `DefaultHasher`, whose algorithm can change between Rust
+/// releases, so the same value gets the same color in every rendered image.
+pub fn hashed_color<T: Hash>(value: T) -> Rgb {
+    let mut hasher = Fnv1a::default();
+    value.hash(&mut hasher);
+    let [r, g, b, ..] = hasher.finish().to_le_bytes();
+    [r | 0x40, g | 0x40, b | 0x40]
+}
+
+/// 64-bit FNV-1a. Integers are fed in little-endian order so colors match across platforms too.
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv1a {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ byte as u64).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn write_u16(&mut self, n: u16) {
+        self.write(&n.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, n: u32) {
+        self.write(&n.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, n: u64) {
+        self.write(&n.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, n: usize) {
+        self.write_u64(n as u64);
+    }
+}
+
+/// An RGB raster where each grid cell is drawn as a `scale`x`scale` block of pixels
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl Image {
+    /// Builds an image by mapping each `(row, col)` cell of a `rows`x`cols` grid to a color
+    pub fn from_fn(
+        rows: usize,
+        cols: usize,
+        scale: usize,
+        color: impl Fn(usize, usize) -> Rgb,
+    ) -> Image {
+        let (width, height) = (cols * scale, rows * scale);
+        let mut pixels = Vec::with_capacity(width * height * 3);
+        for py in 0..height {
+            for px in 0..width {
+                pixels.extend(color(py / scale, px / scale));
+            }
+        }
+        Image {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Color of the pixel at `(row, col)`
+    pub fn pixel(&self, row: usize, col: usize) -> Rgb {
+        let i = (row * self.width + col) * 3;
+        [self.pixels[i], self.pixels[i + 1], self.pixels[i + 2]]
+    }
+
+    /// Encodes the image as a PNG
+    pub fn encode_png<W: Write>(&self, w: W) -> Result<()> {
+        let mut encoder = png::Encoder::new(w, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.pixels)?;
+        Ok(())
+    }
+
+    /// Writes the image to a PNG file
+    pub fn write_png<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let file = File::create(path.as_ref()).context("Unable to create PNG file")?;
+        self.encode_png(BufWriter::new(file))
+    }
+}
+
+/// Encodes frames as an endlessly looping animated GIF
+///
+/// Frames with at most 256 distinct colors (the common case for puzzle grids) share an exact
+/// global palette. Otherwise each frame is quantized individually.
+pub fn encode_gif<W: Write>(w: W, frames: &[Image], delay: Duration) -> Result<()> {
+    let Some(first) = frames.first() else {
+        bail!("Cannot encode a GIF without any frames");
+    };
+    if frames
+        .iter()
+        .any(|f| f.width != first.width || f.height != first.height)
+    {
+        bail!("All GIF frames must have the same dimensions");
+    }
+    let width = u16::try_from(first.width).context("Image too wide for GIF")?;
+    let height = u16::try_from(first.height).context("Image too tall for GIF")?;
+    let delay = (delay.as_millis() / 10) as u16;
+
+    let mut palette: HashMap<Rgb, u8> = HashMap::new();
+    let mut exact = true;
+    for px in frames.iter().flat_map(|f| f.pixels.chunks_exact(3)) {
+        let rgb = [px[0], px[1], px[2]];
+        if !palette.contains_key(&rgb) {
+            if palette.len() == 256 {
+                exact = false;
+                break;
+            }
+            palette.insert(rgb, palette.len() as u8);
+        }
+    }
+    let mut global = vec![0; palette.len() * 3];
+    if exact {
+        for (rgb, i) in &palette {
+            global[*i as usize * 3..*i as usize * 3 + 3].copy_from_slice(rgb);
+        }
+    }
+
+    let mut encoder = gif::Encoder::new(w, width, height, if exact { &global } else { &[] })?;
+    encoder.set_repeat(gif::Repeat::Infinite)?;
+    for frame in frames {
+        let mut gif_frame = if exact {
+            let indices: Vec<u8> = frame
+                .pixels
+                .chunks_exact(3)
+                .map(|px| palette[&[px[0], px[1], px[2]]])
+                .collect();
+            gif::Frame::from_indexed_pixels(width, height, indices, None)
+        } else {
+            gif::Frame::from_rgb_speed(width, height, &frame.pixels, 10)
+        };
+        gif_frame.delay = delay;
+        encoder.write_frame(&gif_frame)?;
+    }
+    Ok(())
+}
+
+/// Saves frames based on the file extension of `path`
+///
+/// - `.gif` writes an animated GIF with `delay` between frames
+/// - `.png` writes a single PNG, or numbered PNGs (`out-0000.png`, ...) for multiple frames
+pub fn save<P: AsRef<Path>>(path: P, frames: &[Image], delay: Duration) -> Result<()> {
+    let path = path.as_ref();
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("gif") => {
+            let file = File::create(path).context("Unable to create GIF file")?;
+            encode_gif(BufWriter::new(file), frames, delay)
+        }
+        Some("png") if frames.len() == 1 => frames[0].write_png(path),
+        Some("png") => {
+            let stem = path.file_stem().unwrap().to_string_lossy();
+            for (i, frame) in frames.iter().enumerate() {
+                let mut frame_path = PathBuf::from(path);
+                frame_path.set_file_name(format!("{stem}-{i:04}.png"));
+                frame.write_png(frame_path)?;
+            }
+            Ok(())
+        }
+        _ => bail!("Unsupported image format: {}", path.display()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn checkerboard(scale: usize) -> Image {
+        Image::from_fn(2, 2, scale, |row, col| {
+            if (row + col) % 2 == 0 {
+                BLACK
+            } else {
+                WHITE
+            }
+        })
+    }
+
+    #[test]
+    fn test_from_fn() {
+        let img = checkerboard(3);
+        assert_eq!((img.width(), img.height()), (6, 6));
+        assert_eq!(img.pixel(0, 0), BLACK);
+        assert_eq!(img.pixel(2, 2), BLACK);
+        assert_eq!(img.pixel(0, 3), WHITE);
+        assert_eq!(img.pixel(5, 5), BLACK);
+    }
+
+    #[test]
+    fn test_encode_png() {
+        let mut buf = Vec::new();
+        checkerboard(2).encode_png(&mut buf).unwrap();
+        assert_eq!(&buf[..8], b"\x89PNG\r\n\x1a\n");
+    }
+
+    #[test]
+    fn test_encode_gif() {
+        let frames = vec![checkerboard(2), Image::from_fn(2, 2, 2, |_, _| RED)];
+        let mut buf = Vec::new();
+        encode_gif(&mut buf, &frames, Duration::from_millis(100)).unwrap();
+        assert_eq!(&buf[..6], b"GIF89a");
+
+        let mismatched = vec![checkerboard(2), checkerboard(3)];
+        assert!(encode_gif(Vec::new(), &mismatched, Duration::ZERO).is_err());
+        assert!(encode_gif(Vec::new(), &[], Duration::ZERO).is_err());
+    }
+
+    #[test]
+    fn test_hashed_color() {
+        assert_eq!(hashed_color('a'), hashed_color('a'));
+        assert_ne!(hashed_color('a'), hashed_color('b'));
+        // Pinned so a change in hashing shows up as a test failure rather than new colors
+        assert_eq!(hashed_color('a'), [0xc4, 0xe9, 0x4f]);
+    }
+}
@@ -0,0 +1,247 @@
+//! Pattern matching in grids of characters, indexed `(row, col)`
+//!
+//! Words can be read in any of the eight directions. Stencils are small 2D patterns with
+//! wildcards, matched in every rotation and reflection.
+
+use anyhow::{bail, Error, Result};
+use grid::Grid;
+use itertools::Itertools;
+use std::str::FromStr;
+
+pub type Pos = (usize, usize);
+
+/// `(row, col)` steps for the eight directions, clockwise from east
+pub const DIRECTIONS: [(isize, isize); 8] = [
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+];
+
+/// A word found in the grid, starting at `start` and reading along `dir`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WordMatch {
+    pub start: Pos,
+    pub dir: (isize, isize),
+}
+
+/// Finds every occurrence of `word` along any of the eight directions
+///
+/// A palindrome is found once in each direction it reads.
+pub fn find_word(grid: &Grid<char>, word: &str) -> Vec<WordMatch> {
+    let word = word.chars().collect_vec();
+    let Some(&first) = word.first() else {
+        return vec![];
+    };
+    let reads = |start: Pos, (dr, dc): (isize, isize)| {
+        word.iter().enumerate().all(|(i, expected)| {
+            let row = start.0.checked_add_signed(dr * i as isize);
+            let col = start.1.checked_add_signed(dc * i as isize);
+            matches!((row, col), (Some(row), Some(col)) if grid.get(row, col) == Some(expected))
+        })
+    };
+
+    grid.indexed_iter()
+        .filter(|(_, c)| **c == first)
+        .flat_map(|(start, _)| {
+            DIRECTIONS
+                .into_iter()
+                .filter(move |dir| reads(start, *dir))
+                .map(move |dir| WordMatch { start, dir })
+        })
+        .collect()
+}
+
+/// A rectangular 2D pattern, where `None` cells match anything
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Stencil {
+    rows: Vec<Vec<Option<char>>>,
+}
+
+/// A stencil found in the grid, in one of its orientations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StencilMatch {
+    /// Where the top-left corner of the oriented stencil lies
+    pub top_left: Pos,
+    /// Index into [`Stencil::orientations`]
+    pub orientation: usize,
+}
+
+impl Stencil {
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn width(&self) -> usize {
+        self.rows.first().map_or(0, Vec::len)
+    }
+
+    /// The stencil turned a quarter turn clockwise
+    pub fn rotate(&self) -> Stencil {
+        let rows = (0..self.width())
+            .map(|col| self.rows.iter().rev().map(|row| row[col]).collect())
+            .collect();
+        Stencil { rows }
+    }
+
+    /// The stencil mirrored left to right
+    pub fn reflect(&self) -> Stencil {
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| row.iter().rev().copied().collect())
+            .collect();
+        Stencil { rows }
+    }
+
+    /// Every distinct rotation and reflection of the stencil, starting with itself
+    ///
+    /// Symmetric stencils have fewer than eight, so a match is never counted twice.
+    pub fn orientations(&self) -> Vec<Stencil> {
+        let mut orientations = Vec::with_capacity(8);
+        let mut stencil = self.clone();
+        for _ in 0..4 {
+            orientations.push(stencil.clone());
+            orientations.push(stencil.reflect());
+            stencil = stencil.rotate();
+        }
+        orientations.into_iter().unique().collect()
+    }
+
+    /// Whether the stencil, as oriented, matches with its top-left corner at `top_left`
+    pub fn matches_at(&self, grid: &Grid<char>, top_left: Pos) -> bool {
+        self.rows.iter().enumerate().all(|(r, row)| {
+            row.iter().enumerate().all(|(c, cell)| match cell {
+                None => top_left.0 + r < grid.rows() && top_left.1 + c < grid.cols(),
+                Some(expected) => grid.get(top_left.0 + r, top_left.1 + c) == Some(expected),
+            })
+        })
+    }
+}
+
+/// Parses lines of characters into a stencil, where '.' is a wildcard
+impl FromStr for Stencil {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let rows = s
+            .trim()
+            .lines()
+            .map(|line| {
+                line.trim()
+                    .chars()
+                    .map(|c| (c != '.').then_some(c))
+                    .collect_vec()
+            })
+            .collect_vec();
+        if !rows.iter().map(Vec::len).all_equal() {
+            bail!("Stencil rows must all be the same width");
+        }
+        Ok(Stencil { rows })
+    }
+}
+
+/// Finds every placement of the stencil, in any rotation or reflection
+pub fn find_stencil(grid: &Grid<char>, stencil: &Stencil) -> Vec<StencilMatch> {
+    let mut found = Vec::new();
+    for (orientation, oriented) in stencil.orientations().iter().enumerate() {
+        let rows = (grid.rows() + 1).saturating_sub(oriented.height());
+        let cols = (grid.cols() + 1).saturating_sub(oriented.width());
+        for top_left in (0..rows).cartesian_product(0..cols) {
+            if oriented.matches_at(grid, top_left) {
+                found.push(StencilMatch {
+                    top_left,
+                    orientation,
+                });
+            }
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn grid(s: &str) -> Grid<char> {
+        crate::parse::parse_2d(s).unwrap()
+    }
+
+    #[test]
+    fn test_find_word() {
+        let g = grid("CAT\nAA.\nT.T\n");
+        let found = find_word(&g, "CAT");
+        assert_eq!(
+            found,
+            vec![
+                WordMatch {
+                    start: (0, 0),
+                    dir: (0, 1)
+                },
+                WordMatch {
+                    start: (0, 0),
+                    dir: (1, 1)
+                },
+                WordMatch {
+                    start: (0, 0),
+                    dir: (1, 0)
+                },
+            ]
+        );
+        assert_eq!(find_word(&g, "TAC").len(), 3);
+        assert_eq!(find_word(&g, "DOG").len(), 0);
+        assert_eq!(find_word(&g, "").len(), 0);
+    }
+
+    #[test]
+    fn test_palindrome() {
+        let g = grid("ABA\n");
+        assert_eq!(find_word(&g, "ABA").len(), 2);
+        assert_eq!(find_word(&g, "A").len(), 16);
+    }
+
+    #[test]
+    fn test_orientations() {
+        let corner: Stencil = "AB\nC.".parse().unwrap();
+        assert_eq!(corner.rotate(), "CA\n.B".parse().unwrap());
+        assert_eq!(corner.reflect(), "BA\n.C".parse().unwrap());
+        assert_eq!(corner.orientations().len(), 8);
+
+        let bar: Stencil = "ABA".parse().unwrap();
+        assert_eq!(bar.orientations().len(), 2);
+        assert_eq!(bar.rotate().height(), 3);
+        assert!("AB\nC".parse::<Stencil>().is_err());
+    }
+
+    #[test]
+    fn test_find_stencil() {
+        let g = grid("XAB\nXC.\nBAX\n");
+        let corner: Stencil = "AB\nC.".parse().unwrap();
+        let found = find_stencil(&g, &corner);
+        // Upright in the top right, and turned halfway around in the bottom left
+        assert_eq!(
+            found,
+            vec![
+                StencilMatch {
+                    top_left: (0, 1),
+                    orientation: 0
+                },
+                StencilMatch {
+                    top_left: (1, 0),
+                    orientation: 4
+                }
+            ]
+        );
+        let orientations = corner.orientations();
+        let found = find_stencil(&grid("XC\nBA\n"), &corner);
+        assert_eq!(found.len(), 1);
+        assert_eq!(
+            orientations[found[0].orientation],
+            ".C\nBA".parse().unwrap()
+        );
+    }
+}